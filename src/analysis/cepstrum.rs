@@ -0,0 +1,394 @@
+use std::f64::consts::PI;
+use num::{self, Zero};
+use strided::{Strided, MutStrided, Stride};
+
+use {Precision, Sample, SampleMut, forward};
+use spectrum;
+
+/// Computes the real cepstrum of the given input and returns a vector of
+/// samples.
+///
+/// The real cepstrum is the inverse transform of the log magnitude spectrum,
+/// the phase is discarded so the input can't be recovered from it.
+#[inline(always)]
+pub fn real<SI, SO, I>(input: I) -> Vec<SO>
+	where SI: Sample,
+	      SO: SampleMut,
+	      I:  Strided<Elem=SI>
+{
+	let mut output = vec![SO::zero(); input.as_stride().len()];
+	real_in(input, &mut *output);
+
+	output
+}
+
+/// Computes the real cepstrum of the given input into the given output of the
+/// same length.
+pub fn real_in<SI, SO, I, O>(input: I, mut output: O)
+	where SI: Sample,
+	      SO: SampleMut,
+	      I:  Strided<Elem=SI>,
+	      O:  MutStrided<Elem=SO>
+{
+	let mut output = output.as_stride_mut();
+
+	debug_assert_eq!(input.as_stride().len(), output.len());
+
+	for (output, value) in output.iter_mut().zip(logarithm(input.as_stride()).iter()) {
+		output.set_normalized(value.re);
+	}
+}
+
+/// Computes the power cepstrum of the given input and returns a vector of
+/// samples.
+///
+/// The power cepstrum is the squared magnitude of the inverse transform of the
+/// log power spectrum, echoes show up as peaks at their delay.
+#[inline(always)]
+pub fn power<SI, SO, I>(input: I) -> Vec<SO>
+	where SI: Sample,
+	      SO: SampleMut,
+	      I:  Strided<Elem=SI>
+{
+	let mut output = vec![SO::zero(); input.as_stride().len()];
+	power_in(input, &mut *output);
+
+	output
+}
+
+/// Computes the power cepstrum of the given input into the given output of
+/// the same length.
+pub fn power_in<SI, SO, I, O>(input: I, mut output: O)
+	where SI: Sample,
+	      SO: SampleMut,
+	      I:  Strided<Elem=SI>,
+	      O:  MutStrided<Elem=SO>
+{
+	let mut output = output.as_stride_mut();
+
+	debug_assert_eq!(input.as_stride().len(), output.len());
+
+	// log |X|² = 2 log |X|
+	for (output, value) in output.iter_mut().zip(logarithm(input.as_stride()).iter()) {
+		output.set_normalized(4.0 * value.norm_sqr());
+	}
+}
+
+/// The linear phase removed from the input by `complex`, it's needed to invert
+/// the cepstrum.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct Phase {
+	/// The delay in samples.
+	pub delay: isize,
+
+	/// Whether the input was negated, a negative sum is a phase of `π` at DC
+	/// which the cepstrum can't hold.
+	pub negative: bool,
+}
+
+/// Computes the complex cepstrum of the given input and returns a vector of
+/// samples and the linear phase removed from the input.
+///
+/// The phase is unwrapped and the linear phase of the delay and of the sign is
+/// removed before the inverse transform.
+#[inline(always)]
+pub fn complex<SI, SO, I>(input: I) -> (Vec<SO>, Phase)
+	where SI: Sample,
+	      SO: SampleMut,
+	      I:  Strided<Elem=SI>
+{
+	let mut output = vec![SO::zero(); input.as_stride().len()];
+	let     phase  = complex_in(input, &mut *output);
+
+	(output, phase)
+}
+
+/// Computes the complex cepstrum of the given input into the given output of
+/// the same length and returns the linear phase removed from the input.
+pub fn complex_in<SI, SO, I, O>(input: I, mut output: O) -> Phase
+	where SI: Sample,
+	      SO: SampleMut,
+	      I:  Strided<Elem=SI>,
+	      O:  MutStrided<Elem=SO>
+{
+	let     input  = input.as_stride();
+	let mut output = output.as_stride_mut();
+	let     length = input.len();
+
+	debug_assert_eq!(length, output.len());
+
+	if length == 0 {
+		return Phase { delay: 0, negative: false };
+	}
+
+	let mut spectrum = forward::<_, num::Complex<Precision>, _>(input);
+	let     half     = length / 2;
+
+	// negate the input so the unwrapped phase starts at zero
+	let negative = spectrum[0].re < 0.0;
+
+	if negative {
+		for value in spectrum.iter_mut() {
+			*value = -*value;
+		}
+	}
+
+	// unwrap the phase over the positive frequencies
+	let phase = spectrum::unwrap(&spectrum[.. half + 1]);
+
+	// the delay is estimated from the phase at the highest frequency
+	let delay = if half == 0 {
+		0
+	}
+	else {
+		(-phase[half] as f64 / omega(half, length)).round() as isize
+	};
+
+	let mut logarithm = vec![num::Complex::<Precision>::zero(); length];
+
+	for (k, &phase) in phase.iter().enumerate() {
+		let value = num::Complex::new(
+			spectrum[k].norm().max(Precision::MIN_POSITIVE).ln(),
+			(phase as f64 + omega(k, length) * delay as f64) as Precision);
+
+		logarithm[k]                     = value;
+		logarithm[(length - k) % length] = value.conj();
+	}
+
+	// DC and Nyquist are real
+	logarithm[0].im = 0.0;
+
+	if length % 2 == 0 {
+		logarithm[half].im = 0.0;
+	}
+
+	for (output, value) in output.iter_mut().zip(::inverse::<_, num::Complex<Precision>, _>(&*logarithm)) {
+		output.set_normalized(value.re);
+	}
+
+	Phase { delay, negative }
+}
+
+/// Reconstructs a signal from the given complex cepstrum and the linear phase
+/// removed by `complex` and returns a vector of samples.
+#[inline(always)]
+pub fn inverse<SI, SO, I>(input: I, phase: Phase) -> Vec<SO>
+	where SI: Sample,
+	      SO: SampleMut,
+	      I:  Strided<Elem=SI>
+{
+	let mut output = vec![SO::zero(); input.as_stride().len()];
+	inverse_in(input, phase, &mut *output);
+
+	output
+}
+
+/// Reconstructs a signal from the given complex cepstrum and the linear phase
+/// removed by `complex` into the given output of the same length.
+pub fn inverse_in<SI, SO, I, O>(input: I, phase: Phase, mut output: O)
+	where SI: Sample,
+	      SO: SampleMut,
+	      I:  Strided<Elem=SI>,
+	      O:  MutStrided<Elem=SO>
+{
+	let     input  = input.as_stride();
+	let mut output = output.as_stride_mut();
+	let     length = input.len();
+
+	debug_assert_eq!(length, output.len());
+
+	if length == 0 {
+		return;
+	}
+
+	let mut spectrum = forward::<_, num::Complex<Precision>, _>(input);
+	let     sign     = if phase.negative { -1.0 } else { 1.0 };
+
+	for k in 0 .. length / 2 + 1 {
+		let value = spectrum[k].exp()
+			* num::Complex::from_polar(&1.0, &((-omega(k, length) * phase.delay as f64) as Precision));
+
+		spectrum[k]                     = value;
+		spectrum[(length - k) % length] = value.conj();
+	}
+
+	for (output, value) in output.iter_mut().zip(::inverse::<_, num::Complex<Precision>, _>(&*spectrum)) {
+		output.set_normalized(value.re * sign);
+	}
+}
+
+/// Computes the minimum-phase signal with the same magnitude spectrum as the
+/// given input and returns a vector of samples.
+///
+/// The real cepstrum is folded onto the positive quefrencies, the input
+/// should be zero-padded enough for the cepstrum not to alias.
+#[inline(always)]
+pub fn minimum<SI, SO, I>(input: I) -> Vec<SO>
+	where SI: Sample,
+	      SO: SampleMut,
+	      I:  Strided<Elem=SI>
+{
+	let mut output = vec![SO::zero(); input.as_stride().len()];
+	minimum_in(input, &mut *output);
+
+	output
+}
+
+/// Computes the minimum-phase signal with the same magnitude spectrum as the
+/// given input into the given output of the same length.
+pub fn minimum_in<SI, SO, I, O>(input: I, mut output: O)
+	where SI: Sample,
+	      SO: SampleMut,
+	      I:  Strided<Elem=SI>,
+	      O:  MutStrided<Elem=SO>
+{
+	let     input  = input.as_stride();
+	let mut output = output.as_stride_mut();
+	let     length = input.len();
+
+	debug_assert_eq!(length, output.len());
+
+	if length == 0 {
+		return;
+	}
+
+	let mut cepstrum = logarithm(input);
+	let     half     = length.div_ceil(2);
+
+	// keep c[0] and the Nyquist term, double the causal part and drop the rest
+	for value in cepstrum[1 .. half].iter_mut() {
+		*value *= 2.0;
+	}
+
+	for value in cepstrum[length / 2 + 1 ..].iter_mut() {
+		*value = num::Complex::zero();
+	}
+
+	let mut spectrum = forward::<_, num::Complex<Precision>, _>(&*cepstrum);
+
+	for value in spectrum.iter_mut() {
+		*value = value.exp();
+	}
+
+	for (output, value) in output.iter_mut().zip(::inverse::<_, num::Complex<Precision>, _>(&*spectrum)) {
+		output.set_normalized(value.re);
+	}
+}
+
+/// The inverse transform of the log magnitude spectrum.
+fn logarithm<S: Sample>(input: Stride<S>) -> Vec<num::Complex<Precision>> {
+	let spectrum = forward::<_, num::Complex<Precision>, _>(input).iter()
+		.map(|c| num::Complex::new(c.norm().max(Precision::MIN_POSITIVE).ln(), 0.0))
+		.collect::<Vec<_>>();
+
+	::inverse::<_, num::Complex<Precision>, _>(&*spectrum)
+}
+
+/// The angular frequency of the given bin.
+#[inline(always)]
+fn omega(bin: usize, length: usize) -> f64 {
+	2.0 * PI * bin as f64 / length as f64
+}
+
+#[cfg(test)]
+mod tests {
+	use Precision;
+
+	fn signal(length: usize) -> Vec<Precision> {
+		// a mixed-phase signal with zeros on both sides of the unit circle
+		let mut signal = vec![0.0; length];
+		signal[3] = 1.0;
+		signal[4] = -2.5;
+		signal[5] = 0.8;
+		signal[6] = 0.9;
+
+		signal
+	}
+
+	#[test]
+	fn complex() {
+		for &length in &[64, 63] {
+			let input             = signal(length);
+			let (cepstrum, phase) = super::complex::<_, Precision, _>(&*input);
+			let output            = super::inverse::<_, Precision, _>(&*cepstrum, phase);
+
+			assert!(!phase.negative);
+
+			for (a, b) in output.iter().zip(input.iter()) {
+				assert!((a - b).abs() < 1e-4, "{} {} {}", length, a, b);
+			}
+		}
+	}
+
+	#[test]
+	fn negative() {
+		for &length in &[64, 63] {
+			let input             = signal(length).iter().map(|v| -v).collect::<Vec<_>>();
+			let (cepstrum, phase) = super::complex::<_, Precision, _>(&*input);
+			let output            = super::inverse::<_, Precision, _>(&*cepstrum, phase);
+
+			assert!(phase.negative);
+
+			// the sign is a phase, the cepstrum is the same as the positive one
+			let (positive, other) = super::complex::<_, Precision, _>(&*signal(length));
+			assert_eq!(phase.delay, other.delay);
+
+			for (a, b) in cepstrum.iter().zip(positive.iter()) {
+				assert!((a - b).abs() < 1e-5);
+			}
+
+			for (a, b) in output.iter().zip(input.iter()) {
+				assert!((a - b).abs() < 1e-4, "{} {} {}", length, a, b);
+			}
+		}
+	}
+
+	#[test]
+	fn real() {
+		// an echo at 20 samples shows at quefrency 20
+		let mut input = vec![0.0; 128];
+
+		for (n, value) in input.iter_mut().enumerate().take(40) {
+			*value = (n as Precision * 0.9).sin() * (-(n as Precision) / 8.0).exp();
+		}
+
+		let direct = input.clone();
+
+		for (n, value) in direct.iter().enumerate().take(100) {
+			input[n + 20] += 0.5 * value;
+		}
+
+		let cepstrum = super::power::<_, Precision, _>(&*input);
+		let peak     = (10 .. 64).max_by(|&a, &b| cepstrum[a].partial_cmp(&cepstrum[b]).unwrap()).unwrap();
+
+		assert_eq!(peak, 20);
+
+		// the real cepstrum is even
+		let cepstrum = super::real::<_, Precision, _>(&*input);
+
+		for k in 1 .. 64 {
+			assert!((cepstrum[k] - cepstrum[128 - k]).abs() < 1e-4);
+		}
+	}
+
+	#[test]
+	fn minimum() {
+		let input  = signal(64);
+		let output = super::minimum::<_, Precision, _>(&*input);
+
+		// same magnitude spectrum
+		let a = ::forward::<_, ::num::Complex<Precision>, _>(&*input);
+		let b = ::forward::<_, ::num::Complex<Precision>, _>(&*output);
+
+		for (a, b) in a.iter().zip(b.iter()) {
+			assert!((a.norm() - b.norm()).abs() < 1e-3);
+		}
+
+		// the energy is concentrated at the start
+		let energy = |signal: &[Precision], end: usize| signal[.. end].iter().map(|v| v * v).sum::<Precision>();
+
+		for end in 1 .. 10 {
+			assert!(energy(&output, end) >= energy(&input, end) - 1e-4);
+		}
+	}
+}
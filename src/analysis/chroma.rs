@@ -0,0 +1,315 @@
+use std::f64::consts::PI;
+use strided::Strided;
+
+use {Precision, Complex};
+use spectrum::bandwidth;
+use spectrum::peak::parabolic;
+use super::cqt::Kernel;
+
+/// The number of pitch classes.
+pub const CLASSES: usize = 12;
+
+/// The tuning used to map frequencies to pitch classes.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum Tuning {
+	/// The given frequency of A4.
+	Reference(Precision),
+
+	/// The frequency of A4 is estimated from the spectral peaks of the input.
+	Estimate,
+}
+
+/// The normalization applied to the chroma vector.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Normalization {
+	/// The energies are left as they are.
+	None,
+
+	/// The largest class is `1.0`.
+	Max,
+
+	/// The classes sum to `1.0`.
+	Sum,
+
+	/// The vector has unit Euclidean length.
+	Euclidean,
+}
+
+/// The parameters of the chroma computation.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct Parameters {
+	/// The tuning reference.
+	pub tuning: Tuning,
+
+	/// The lowest frequency taken into account.
+	pub min: Precision,
+
+	/// The highest frequency taken into account.
+	pub max: Precision,
+
+	/// The normalization of the output.
+	pub normalization: Normalization,
+}
+
+impl Default for Parameters {
+	fn default() -> Self {
+		Parameters {
+			tuning:        Tuning::Reference(440.0),
+			min:           55.0,
+			max:           5000.0,
+			normalization: Normalization::Max,
+		}
+	}
+}
+
+/// Returns the fractional pitch class of the given frequency, `0.0` is C and
+/// `9.0` is A.
+#[inline]
+pub fn class(frequency: Precision, tuning: Precision) -> Precision {
+	let semitones = 12.0 * (frequency / tuning).log2() + 9.0;
+
+	semitones - (semitones / 12.0).floor() * 12.0
+}
+
+/// Computes the chroma vector from a magnitude spectrum as returned by
+/// `spectrum::compute` for the given sample rate.
+///
+/// The output has `CLASSES` values starting from C.
+pub fn compute<I>(parameters: &Parameters, rate: u32, input: I) -> Vec<Precision>
+	where I: Strided<Elem=Precision>
+{
+	let input     = input.as_stride();
+	let bandwidth = bandwidth((input.len() - 1) * 2, rate);
+
+	let bins = input.iter().enumerate()
+		.map(|(bin, &value)| (bin as Precision * bandwidth, value));
+
+	fold(parameters, bins, || tuning(rate, parameters.min, parameters.max, input))
+}
+
+/// Computes the chroma vector from the coefficients of a constant-Q transform
+/// computed with the given kernel.
+///
+/// The output has `CLASSES` values starting from C.
+pub fn constant_q<C, I>(parameters: &Parameters, kernel: &Kernel, input: I) -> Vec<Precision>
+	where C: Complex,
+	      I: Strided<Elem=C>
+{
+	let input = input.as_stride();
+
+	let bins = input.iter().enumerate()
+		.map(|(bin, value)| (kernel.frequency(bin), value.to_num().norm()))
+		.collect::<Vec<_>>();
+
+	fold(parameters, bins.iter().cloned(), || constant_q_tuning(kernel, parameters.min, parameters.max, input))
+}
+
+/// Estimates the frequency of A4 from the peaks of a magnitude spectrum as
+/// returned by `spectrum::compute` for the given sample rate.
+///
+/// The deviation of every peak from the closest equal-tempered pitch is
+/// averaged on the circle, weighted by the peak magnitude.
+pub fn tuning<I>(rate: u32, min: Precision, max: Precision, input: I) -> Precision
+	where I: Strided<Elem=Precision>
+{
+	let input     = input.as_stride();
+	let bandwidth = bandwidth((input.len() - 1) * 2, rate);
+	let bins      = input.iter().cloned().collect::<Vec<_>>();
+
+	peaks(&bins, min, max, |bin, offset| (bin as Precision + offset) * bandwidth)
+}
+
+/// Estimates the frequency of A4 from the peaks of the coefficients of a
+/// constant-Q transform computed with the given kernel.
+pub fn constant_q_tuning<C, I>(kernel: &Kernel, min: Precision, max: Precision, input: I) -> Precision
+	where C: Complex,
+	      I: Strided<Elem=C>
+{
+	let bins = input.as_stride().iter().map(|value| value.to_num().norm()).collect::<Vec<_>>();
+
+	// the bins are geometrically spaced
+	peaks(&bins, min, max, |bin, offset|
+		kernel.frequency(bin) * (kernel.frequency(bin + 1) / kernel.frequency(bin)).powf(offset))
+}
+
+/// Normalizes the given chroma vector in-place.
+pub fn normalize(chroma: &mut [Precision], normalization: Normalization) {
+	let factor = match normalization {
+		Normalization::None =>
+			return,
+
+		Normalization::Max =>
+			chroma.iter().fold(0.0, |max: Precision, &v| max.max(v)),
+
+		Normalization::Sum =>
+			chroma.iter().sum(),
+
+		Normalization::Euclidean =>
+			chroma.iter().map(|v| v * v).sum::<Precision>().sqrt(),
+	};
+
+	if factor > 0.0 {
+		for value in chroma.iter_mut() {
+			*value /= factor;
+		}
+	}
+}
+
+/// Accumulates the energy of the given frequency and magnitude pairs into
+/// pitch classes.
+fn fold<B, T>(parameters: &Parameters, bins: B, estimate: T) -> Vec<Precision>
+	where B: Iterator<Item=(Precision, Precision)>,
+	      T: FnOnce() -> Precision
+{
+	let reference = match parameters.tuning {
+		Tuning::Reference(frequency) => frequency,
+		Tuning::Estimate             => estimate(),
+	};
+
+	let mut output = vec![0.0; CLASSES];
+
+	for (frequency, magnitude) in bins {
+		if frequency < parameters.min || frequency > parameters.max {
+			continue;
+		}
+
+		let class = class(frequency, reference).round() as usize % CLASSES;
+		output[class] += magnitude * magnitude;
+	}
+
+	normalize(&mut output, parameters.normalization);
+
+	output
+}
+
+/// Estimates the frequency of A4 from the local maxima of the given
+/// magnitudes, the given function maps a bin and a fractional offset to its
+/// frequency.
+///
+/// Every peak is refined with a parabola through the log magnitudes of its
+/// neighbours, the bin centers are too coarse to measure the deviation.
+fn peaks<F>(bins: &[Precision], min: Precision, max: Precision, frequency: F) -> Precision
+	where F: Fn(usize, Precision) -> Precision
+{
+	let mut real = 0.0;
+	let mut imag = 0.0;
+
+	for (index, window) in bins.windows(3).enumerate() {
+		let magnitude = window[1];
+
+		if magnitude <= window[0] || magnitude < window[2] {
+			continue;
+		}
+
+		let floor     = Precision::MIN_POSITIVE;
+		let offset    = parabolic(window[0].max(floor).ln(), magnitude.ln(), window[2].max(floor).ln());
+		let frequency = frequency(index + 1, offset.clamp(-0.5, 0.5));
+
+		if frequency <= 0.0 || frequency < min || frequency > max {
+			continue;
+		}
+
+		// deviation from the closest pitch in semitones, as an angle
+		let semitones = 12.0 * (frequency / 440.0).log2();
+		let angle     = 2.0 * PI as Precision * (semitones - semitones.round());
+
+		real += magnitude * angle.cos();
+		imag += magnitude * angle.sin();
+	}
+
+	if real == 0.0 && imag == 0.0 {
+		return 440.0;
+	}
+
+	let deviation = imag.atan2(real) / (2.0 * PI as Precision);
+
+	440.0 * (2.0 as Precision).powf(deviation / 12.0)
+}
+
+#[cfg(test)]
+mod tests {
+	use std::f64::consts::PI;
+	use num::Complex;
+	use super::{Parameters, Tuning, Normalization};
+	use analysis::cqt::Kernel;
+	use window::{self, Hann, Symmetry};
+	use {Precision, spectrum, forward};
+
+	macro_rules! assert_approx_eq {
+		($a:expr, $b:expr, $p:expr) => (
+			assert_eq!(format!("{:.1$}", $a, $p), format!("{:.1$}", $b, $p));
+		)
+	}
+
+	fn spectrum(rate: u32, size: usize, frequencies: &[Precision]) -> Vec<Precision> {
+		let bandwidth = rate as Precision / size as Precision;
+
+		(0 .. size / 2 + 1).map(|bin| {
+			let frequency = bin as Precision * bandwidth;
+
+			frequencies.iter()
+				.map(|&f| (1.0 - ((frequency - f) / bandwidth).abs()).max(0.0))
+				.sum()
+		}).collect()
+	}
+
+	#[test]
+	fn class() {
+		assert_approx_eq!(super::class(440.0, 440.0), 9.0, 2);
+		assert_approx_eq!(super::class(261.63, 440.0), 0.0, 2);
+		assert_approx_eq!(super::class(880.0, 440.0), 9.0, 2);
+		assert_approx_eq!(super::class(466.16, 440.0), 10.0, 2);
+	}
+
+	#[test]
+	fn compute() {
+		// C major triad
+		let input  = spectrum(44100, 16384, &[261.63, 329.63, 392.0]);
+		let output = super::compute(&Parameters::default(), 44100, &*input);
+
+		assert_eq!(output.len(), 12);
+		assert!(output[0] > 0.5);
+		assert!(output[4] > 0.5);
+		assert!(output[7] > 0.5);
+		assert!(output[1] < 0.1);
+		assert!(output[9] < 0.1);
+	}
+
+	fn tone(size: usize, rate: u32, fundamental: Precision) -> Vec<Precision> {
+		(0 .. size).map(|n| (1 .. 5).map(|k|
+			(2.0 * PI as Precision * fundamental * k as Precision * n as Precision / rate as Precision).sin() / k as Precision
+		).sum()).collect()
+	}
+
+	#[test]
+	fn tuning() {
+		// A4 tuned to 432 Hz, analyzed in a realistic frame
+		for &reference in &[432.0, 440.0, 445.0] {
+			let window   = window::generate::<Hann, Precision, _>(.., Symmetry::Symmetric, 4096);
+			let frame    = window.apply::<Precision, _, _>(&*tone(4096, 44100, reference / 2.0));
+			let spectrum = spectrum::compute(&*forward::<_, Complex<Precision>, _>(&*frame));
+
+			assert_approx_eq!(super::tuning(44100, 55.0, 5000.0, &*spectrum), reference, 0);
+		}
+
+		let input = spectrum(44100, 16384, &[216.0, 432.0, 648.0, 864.0]);
+
+		let parameters = Parameters { tuning: Tuning::Estimate, normalization: Normalization::Sum, .. Parameters::default() };
+		let output     = super::compute(&parameters, 44100, &*input);
+
+		assert_approx_eq!(output.iter().sum::<Precision>(), 1.0, 3);
+	}
+
+	#[test]
+	fn constant_q_tuning() {
+		let kernel = Kernel::new(44100, 110.0, 12, 4);
+
+		for &reference in &[432.0, 440.0, 448.0] {
+			let input  = tone(kernel.size(), 44100, reference / 2.0);
+			let output = kernel.transform::<_, Complex<Precision>, _>(&*input);
+			let tuned  = super::constant_q_tuning(&kernel, 55.0, 5000.0, &*output);
+
+			assert!((tuned - reference).abs() < 1.5, "{} {}", tuned, reference);
+		}
+	}
+}
@@ -0,0 +1,230 @@
+use std::f64::consts::PI;
+use num::{self, Zero};
+use strided::{Strided, MutStrided};
+
+use {Precision, Complex, ComplexMut, Sample, forward, forward_in};
+use window::{self, Hamming, Symmetry};
+use super::frames;
+
+/// The default threshold below which spectral kernel values are dropped.
+pub const THRESHOLD: Precision = 0.0054;
+
+/// Precomputed sparse spectral kernels for the efficient constant-Q transform
+/// by Brown and Puckette.
+///
+/// Every bin is the correlation of the frame with a windowed complex
+/// exponential whose length is inversely proportional to the bin frequency, so
+/// all the bins have the same quality factor. The correlation is computed in
+/// the frequency domain against sparse spectral kernels, so every frame needs a
+/// single FFT.
+#[derive(Clone, Debug)]
+pub struct Kernel {
+	rate:    u32,
+	min:     Precision,
+	bins:    usize,
+	octaves: usize,
+	size:    usize,
+	kernels: Vec<Vec<(usize, num::Complex<Precision>)>>,
+}
+
+impl Kernel {
+	/// Creates the kernels for the given sample rate, minimum frequency, number
+	/// of bins per octave and number of octaves.
+	pub fn new(rate: u32, min: Precision, bins: usize, octaves: usize) -> Self {
+		Kernel::with_threshold(rate, min, bins, octaves, THRESHOLD)
+	}
+
+	/// Creates the kernels dropping spectral values smaller than the given
+	/// threshold, a lower threshold is more accurate but slower.
+	pub fn with_threshold(rate: u32, min: Precision, bins: usize, octaves: usize, threshold: Precision) -> Self {
+		debug_assert!(bins > 0 && octaves > 0);
+		debug_assert!(min * (2.0 as Precision).powf(octaves as Precision) <= rate as Precision / 2.0);
+
+		let q    = quality(bins);
+		let size = length(q, rate, min).next_power_of_two();
+
+		let mut kernels  = Vec::with_capacity(bins * octaves);
+		let mut temporal = vec![num::Complex::<Precision>::zero(); size];
+
+		for k in 0 .. bins * octaves {
+			let frequency = min * (2.0 as Precision).powf(k as Precision / bins as Precision);
+			let width     = length(q, rate, frequency);
+			let start     = (size - width) / 2;
+			let window    = window::generate::<Hamming, Precision, _>(.., Symmetry::Symmetric, width);
+
+			for value in temporal.iter_mut() {
+				*value = num::Complex::zero();
+			}
+
+			// centered windowed complex exponential
+			for n in 0 .. width {
+				temporal[start + n] = num::Complex::from_polar(&(window[n] / width as Precision),
+					&(2.0 * PI as Precision * q * n as Precision / width as Precision));
+			}
+
+			let spectral: Vec<num::Complex<Precision>> = forward(&*temporal);
+
+			kernels.push(spectral.iter().enumerate()
+				.filter(|&(_, value)| value.norm() >= threshold)
+				.map(|(index, value)| (index, value.conj() / size as Precision))
+				.collect());
+		}
+
+		Kernel {
+			rate,
+			min,
+			bins,
+			octaves,
+			size,
+			kernels,
+		}
+	}
+
+	/// The frame size the transform works on.
+	#[inline(always)]
+	pub fn size(&self) -> usize {
+		self.size
+	}
+
+	/// The number of output bins.
+	#[inline(always)]
+	pub fn len(&self) -> usize {
+		self.bins * self.octaves
+	}
+
+	/// Checks if there are no output bins.
+	#[inline(always)]
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	/// The quality factor of the bins.
+	#[inline(always)]
+	pub fn quality(&self) -> Precision {
+		quality(self.bins)
+	}
+
+	/// The center frequency of the given bin.
+	#[inline]
+	pub fn frequency(&self, bin: usize) -> Precision {
+		self.min * (2.0 as Precision).powf(bin as Precision / self.bins as Precision)
+	}
+
+	/// The sample rate the kernels were computed for.
+	#[inline(always)]
+	pub fn rate(&self) -> u32 {
+		self.rate
+	}
+
+	/// Transforms a single frame of `size` samples and returns the coefficients.
+	pub fn transform<CI, CO, I>(&self, input: I) -> Vec<CO>
+		where CI: Complex,
+		      CO: ComplexMut,
+		      I:  Strided<Elem=CI>
+	{
+		let mut output = vec![CO::zero(); self.len()];
+		self.transform_in(input, &mut *output);
+
+		output
+	}
+
+	/// Transforms a single frame of `size` samples into the given output.
+	pub fn transform_in<CI, CO, I, O>(&self, input: I, mut output: O)
+		where CI: Complex,
+		      CO: ComplexMut,
+		      I:  Strided<Elem=CI>,
+		      O:  MutStrided<Elem=CO>
+	{
+		let     input  = input.as_stride();
+		let mut output = output.as_stride_mut();
+
+		debug_assert_eq!(input.len(), self.size);
+		debug_assert_eq!(output.len(), self.len());
+
+		let mut spectrum = vec![num::Complex::<Precision>::zero(); self.size];
+		forward_in(input, &mut *spectrum);
+
+		for (output, kernel) in output.iter_mut().zip(self.kernels.iter()) {
+			let mut sum = num::Complex::<Precision>::zero();
+
+			for &(index, value) in kernel {
+				sum += spectrum[index] * value;
+			}
+
+			output.set(&sum);
+		}
+	}
+
+	/// Transforms the given input in frames of `size` samples every `hop`
+	/// samples, the last frame is zero-padded.
+	///
+	/// The coefficients of a frame are centered on the middle of the frame.
+	pub fn frames<S, I>(&self, input: I, hop: usize) -> Vec<Vec<num::Complex<Precision>>>
+		where S: Sample,
+		      I: Strided<Elem=S>
+	{
+		let     input = input.as_stride();
+		let mut frame = vec![0.0 as Precision; self.size];
+
+		(0 .. frames(input.len(), self.size, hop)).map(|index| {
+			for (i, value) in frame.iter_mut().enumerate() {
+				*value = input.get(index * hop + i).map(|s| s.normalize()).unwrap_or(0.0);
+			}
+
+			self.transform(&*frame)
+		}).collect()
+	}
+}
+
+/// The quality factor for the given number of bins per octave.
+#[inline]
+fn quality(bins: usize) -> Precision {
+	1.0 / ((2.0 as Precision).powf(1.0 / bins as Precision) - 1.0)
+}
+
+/// The kernel length for the given quality factor and frequency.
+#[inline]
+fn length(q: Precision, rate: u32, frequency: Precision) -> usize {
+	(q * rate as Precision / frequency).ceil() as usize
+}
+
+#[cfg(test)]
+mod tests {
+	use std::f64::consts::PI;
+	use super::Kernel;
+	use Precision;
+
+	#[test]
+	fn frequency() {
+		let kernel = Kernel::new(8000, 110.0, 12, 3);
+
+		assert_eq!(kernel.len(), 36);
+		assert_eq!(kernel.frequency(12).round(), 220.0);
+		assert!(kernel.size().is_power_of_two());
+	}
+
+	#[test]
+	fn peak() {
+		let kernel = Kernel::new(8000, 110.0, 12, 3);
+		let input  = (0 .. kernel.size())
+			.map(|n| (2.0 * PI as Precision * 440.0 * n as Precision / 8000.0).sin())
+			.collect::<Vec<_>>();
+
+		let output = kernel.transform::<_, ::num::Complex<Precision>, _>(&*input);
+		let peak   = output.iter().enumerate()
+			.max_by(|a, b| a.1.norm().partial_cmp(&b.1.norm()).unwrap())
+			.unwrap().0;
+
+		assert_eq!(peak, 24);
+		assert!((output[24].norm() - 0.5 * 0.54).abs() < 0.05);
+	}
+
+	#[test]
+	fn frames() {
+		let kernel = Kernel::new(8000, 220.0, 12, 2);
+		let input  = vec![0.0 as Precision; kernel.size() * 2];
+
+		assert_eq!(kernel.frames(&*input, kernel.size()).len(), 2);
+		assert_eq!(kernel.frames(&*input, kernel.size() / 2).len(), 3);
+	}
+}
@@ -0,0 +1,251 @@
+use strided::{Strided, Stride};
+
+use {Precision};
+use spectrum::frequency;
+
+/// Returns an iterator over the frequency and magnitude of every bin in the
+/// given magnitude spectrum, mapped like `spectrum::frequency`.
+fn bins<'a>(input: Stride<'a, Precision>, rate: u32) -> impl Iterator<Item=(Precision, Precision)> + 'a {
+	let size = (input.len() - 1) * 2;
+
+	input.iter().enumerate().map(move |(bin, &value)| (frequency(bin, size, rate), value))
+}
+
+/// Computes the spectral centroid in hertz of the given magnitude spectrum, as
+/// returned by `spectrum::compute`.
+pub fn centroid<I>(input: I, rate: u32) -> Precision
+	where I: Strided<Elem=Precision>
+{
+	let input = input.as_stride();
+	let total = input.iter().sum::<Precision>();
+
+	if total == 0.0 {
+		return 0.0;
+	}
+
+	bins(input, rate).map(|(f, m)| f * m).sum::<Precision>() / total
+}
+
+/// Computes the `n`th central moment of the given magnitude spectrum around its
+/// centroid.
+fn moment(input: Stride<Precision>, rate: u32, n: i32) -> Precision {
+	let total    = input.iter().sum::<Precision>();
+	let centroid = centroid(input, rate);
+
+	if total == 0.0 {
+		return 0.0;
+	}
+
+	bins(input, rate).map(|(f, m)| (f - centroid).powi(n) * m).sum::<Precision>() / total
+}
+
+/// Computes the spectral spread in hertz, the standard deviation around the
+/// centroid.
+pub fn spread<I>(input: I, rate: u32) -> Precision
+	where I: Strided<Elem=Precision>
+{
+	moment(input.as_stride(), rate, 2).sqrt()
+}
+
+/// Computes the spectral skewness, the asymmetry around the centroid.
+pub fn skewness<I>(input: I, rate: u32) -> Precision
+	where I: Strided<Elem=Precision>
+{
+	let input  = input.as_stride();
+	let spread = spread(input, rate);
+
+	if spread == 0.0 {
+		return 0.0;
+	}
+
+	moment(input, rate, 3) / spread.powi(3)
+}
+
+/// Computes the spectral kurtosis, the flatness around the centroid.
+pub fn kurtosis<I>(input: I, rate: u32) -> Precision
+	where I: Strided<Elem=Precision>
+{
+	let input  = input.as_stride();
+	let spread = spread(input, rate);
+
+	if spread == 0.0 {
+		return 0.0;
+	}
+
+	moment(input, rate, 4) / spread.powi(4)
+}
+
+/// Computes the spectral flatness, the ratio between the geometric and the
+/// arithmetic mean of the power spectrum, `1.0` for white noise and close to
+/// `0.0` for a pure tone.
+pub fn flatness<I>(input: I) -> Precision
+	where I: Strided<Elem=Precision>
+{
+	let input  = input.as_stride();
+	let length = input.len() as Precision;
+
+	let arithmetic = input.iter().map(|m| m * m).sum::<Precision>() / length;
+	let geometric  = (input.iter().map(|m| (m * m).max(Precision::MIN_POSITIVE).ln()).sum::<Precision>() / length).exp();
+
+	if arithmetic == 0.0 {
+		return 0.0;
+	}
+
+	geometric / arithmetic
+}
+
+/// Computes the spectral crest, the ratio between the maximum and the mean of
+/// the magnitude spectrum.
+pub fn crest<I>(input: I) -> Precision
+	where I: Strided<Elem=Precision>
+{
+	let input = input.as_stride();
+	let mean  = input.iter().sum::<Precision>() / input.len() as Precision;
+	let max   = input.iter().fold(0.0, |max: Precision, &m| max.max(m));
+
+	if mean == 0.0 {
+		return 0.0;
+	}
+
+	max / mean
+}
+
+/// Computes the frequency in hertz below which the given percentile, between
+/// `0.0` and `1.0`, of the spectral energy lies.
+pub fn rolloff<I>(input: I, rate: u32, percentile: Precision) -> Precision
+	where I: Strided<Elem=Precision>
+{
+	let input     = input.as_stride();
+	let threshold = input.iter().map(|m| m * m).sum::<Precision>() * percentile;
+
+	let mut energy = 0.0;
+	let mut last   = 0.0;
+
+	for (f, m) in bins(input, rate) {
+		energy += m * m;
+		last    = f;
+
+		if energy >= threshold {
+			break;
+		}
+	}
+
+	last
+}
+
+/// Computes the spectral slope, the slope of the linear regression of the
+/// magnitude over the frequency in hertz.
+pub fn slope<I>(input: I, rate: u32) -> Precision
+	where I: Strided<Elem=Precision>
+{
+	let input  = input.as_stride();
+	let length = input.len() as Precision;
+
+	let mean_f = bins(input, rate).map(|(f, _)| f).sum::<Precision>() / length;
+	let mean_m = input.iter().sum::<Precision>() / length;
+
+	let (covariance, variance) = bins(input, rate).fold((0.0, 0.0), |(c, v): (Precision, Precision), (f, m)|
+		(c + (f - mean_f) * (m - mean_m), v + (f - mean_f) * (f - mean_f)));
+
+	if variance == 0.0 {
+		return 0.0;
+	}
+
+	covariance / variance
+}
+
+/// Computes the normalized spectral entropy of the power spectrum, `1.0` for
+/// white noise and `0.0` for a single bin.
+pub fn entropy<I>(input: I) -> Precision
+	where I: Strided<Elem=Precision>
+{
+	let input = input.as_stride();
+	let total = input.iter().map(|m| m * m).sum::<Precision>();
+
+	if total == 0.0 || input.len() < 2 {
+		return 0.0;
+	}
+
+	let entropy = input.iter()
+		.map(|m| m * m / total)
+		.filter(|&p| p > 0.0)
+		.map(|p| -p * p.log2())
+		.sum::<Precision>();
+
+	entropy / (input.len() as Precision).log2()
+}
+
+/// Computes the spectral flux between two consecutive magnitude spectra, the
+/// Euclidean distance between them.
+pub fn flux<P, C>(previous: P, current: C) -> Precision
+	where P: Strided<Elem=Precision>,
+	      C: Strided<Elem=Precision>
+{
+	let previous = previous.as_stride();
+	let current  = current.as_stride();
+
+	debug_assert_eq!(previous.len(), current.len());
+
+	previous.iter().zip(current.iter())
+		.map(|(p, c)| (c - p) * (c - p))
+		.sum::<Precision>()
+		.sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+	use spectrum;
+
+	macro_rules! fix {
+		($a:expr) => (
+			if $a == "-0.00" {
+				"0.00".to_owned()
+			}
+			else {
+				$a
+			}
+		)
+	}
+
+	macro_rules! assert_approx_eq {
+		($a:expr, $b:expr, $p:expr) => (
+			assert_eq!(fix!(format!("{:.1$}", $a, $p)), fix!(format!("{:.1$}", $b, $p)));
+		)
+	}
+
+	#[test]
+	fn tone() {
+		let mut input = vec![0.0; 513];
+		input[100] = 1.0;
+
+		let frequency = spectrum::frequency(100, 1024, 44100);
+
+		assert_approx_eq!(super::centroid(&*input, 44100), frequency, 2);
+		assert_approx_eq!(super::spread(&*input, 44100), 0.0, 2);
+		assert_approx_eq!(super::rolloff(&*input, 44100, 0.85), frequency, 2);
+		assert_approx_eq!(super::flatness(&*input), 0.0, 2);
+		assert_approx_eq!(super::crest(&*input), 513.0, 2);
+		assert_approx_eq!(super::entropy(&*input), 0.0, 2);
+	}
+
+	#[test]
+	fn flat() {
+		let input = vec![1.0; 513];
+
+		assert_approx_eq!(super::centroid(&*input, 44100), 11025.0, 0);
+		assert_approx_eq!(super::skewness(&*input, 44100), 0.0, 2);
+		assert_approx_eq!(super::kurtosis(&*input, 44100), 1.8, 1);
+		assert_approx_eq!(super::flatness(&*input), 1.0, 2);
+		assert_approx_eq!(super::crest(&*input), 1.0, 2);
+		assert_approx_eq!(super::entropy(&*input), 1.0, 2);
+		assert_approx_eq!(super::slope(&*input, 44100), 0.0, 2);
+	}
+
+	#[test]
+	fn flux() {
+		let previous = vec![1.0, 1.0, 1.0];
+		let current  = vec![1.0, 4.0, 5.0];
+
+		assert_approx_eq!(super::flux(&*previous, &*current), 5.0, 2);
+	}
+}
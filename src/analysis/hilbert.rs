@@ -0,0 +1,175 @@
+use std::f64::consts::PI;
+use num;
+use strided::{Strided, MutStrided};
+
+use {Precision, Sample, SampleMut, ComplexMut, forward, inverse};
+use spectrum;
+
+/// Computes the analytic signal of the given real input and returns a vector
+/// of complex numbers.
+///
+/// The real part is the input and the imaginary part its Hilbert transform.
+#[inline(always)]
+pub fn analytic<S, C, I>(input: I) -> Vec<C>
+	where S: Sample,
+	      C: ComplexMut,
+	      I: Strided<Elem=S>
+{
+	let mut output = vec![C::zero(); input.as_stride().len()];
+	analytic_in(input, &mut *output);
+
+	output
+}
+
+/// Computes the analytic signal of the given real input into the given output
+/// of the same length.
+pub fn analytic_in<S, C, I, O>(input: I, mut output: O)
+	where S: Sample,
+	      C: ComplexMut,
+	      I: Strided<Elem=S>,
+	      O: MutStrided<Elem=C>
+{
+	let     input  = input.as_stride();
+	let mut output = output.as_stride_mut();
+	let     length = input.len();
+
+	debug_assert_eq!(length, output.len());
+
+	if length == 0 {
+		return;
+	}
+
+	let mut spectrum = forward::<_, num::Complex<Precision>, _>(input);
+
+	// the positive frequencies are doubled and the negative ones removed, DC
+	// and for even lengths Nyquist belong to both so they're kept as they are
+	let half = length.div_ceil(2);
+
+	for value in spectrum[1 .. half].iter_mut() {
+		*value *= 2.0;
+	}
+
+	for value in spectrum[length / 2 + 1 ..].iter_mut() {
+		*value = num::Complex::new(0.0, 0.0);
+	}
+
+	for (output, value) in output.iter_mut().zip(inverse::<_, num::Complex<Precision>, _>(&*spectrum)) {
+		output.set(&value);
+	}
+}
+
+/// Computes the amplitude envelope of the given real input and returns a
+/// vector of samples.
+#[inline(always)]
+pub fn envelope<SI, SO, I>(input: I) -> Vec<SO>
+	where SI: Sample,
+	      SO: SampleMut,
+	      I:  Strided<Elem=SI>
+{
+	let mut output = vec![SO::zero(); input.as_stride().len()];
+	envelope_in(input, &mut *output);
+
+	output
+}
+
+/// Computes the amplitude envelope of the given real input into the given
+/// output of the same length.
+pub fn envelope_in<SI, SO, I, O>(input: I, mut output: O)
+	where SI: Sample,
+	      SO: SampleMut,
+	      I:  Strided<Elem=SI>,
+	      O:  MutStrided<Elem=SO>
+{
+	let signal = analytic::<_, num::Complex<Precision>, _>(input);
+
+	for (output, value) in output.as_stride_mut().iter_mut().zip(signal.iter()) {
+		output.set_normalized(value.norm());
+	}
+}
+
+/// Computes the unwrapped instantaneous phase in radians of the given real
+/// input.
+pub fn phase<S, I>(input: I) -> Vec<Precision>
+	where S: Sample,
+	      I: Strided<Elem=S>
+{
+	spectrum::unwrap(&*analytic::<_, num::Complex<Precision>, _>(input))
+}
+
+/// Computes the instantaneous frequency in hertz of the given real input.
+///
+/// The frequency is the difference of consecutive phases, so there is one
+/// value less than the input, the `n`-th value is between samples `n` and
+/// `n + 1`.
+pub fn frequency<S, I>(input: I, rate: u32) -> Vec<Precision>
+	where S: Sample,
+	      I: Strided<Elem=S>
+{
+	let phase = phase(input);
+	let scale = rate as Precision / (2.0 * PI as Precision);
+
+	phase.windows(2).map(|pair| (pair[1] - pair[0]) * scale).collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use std::f64::consts::PI;
+	use num::Complex;
+	use Precision;
+
+	fn tone(length: usize, frequency: Precision, rate: Precision) -> Vec<Precision> {
+		(0 .. length).map(|n| (2.0 * PI as Precision * frequency * n as Precision / rate).cos()).collect()
+	}
+
+	#[test]
+	fn analytic() {
+		// periodic tones give exact sines for both parities
+		for &length in &[64, 65] {
+			let input  = (0 .. length).map(|n| (2.0 * PI as Precision * 5.0 * n as Precision / length as Precision).cos())
+				.collect::<Vec<_>>();
+			let output = super::analytic::<_, Complex<Precision>, _>(&*input);
+
+			for (n, value) in output.iter().enumerate() {
+				let expected = Complex::from_polar(&1.0, &(2.0 * PI as Precision * 5.0 * n as Precision / length as Precision));
+				assert!((value - expected).norm() < 1e-4, "{} {} {}", length, value, expected);
+			}
+		}
+
+		// DC and Nyquist have no Hilbert transform
+		let output = super::analytic::<_, Complex<Precision>, _>(&[1.0, -1.0, 1.0, -1.0][..]);
+
+		for (value, &expected) in output.iter().zip([1.0, -1.0, 1.0, -1.0].iter()) {
+			assert!((value - Complex::new(expected, 0.0)).norm() < 1e-6);
+		}
+	}
+
+	#[test]
+	fn envelope() {
+		let input = (0 .. 1000).map(|n| {
+			let t = n as Precision / 1000.0;
+			(1.0 + 0.5 * (2.0 * PI as Precision * 3.0 * t).cos()) * (2.0 * PI as Precision * 100.0 * t).cos()
+		}).collect::<Vec<_>>();
+
+		let output = super::envelope::<_, Precision, _>(&*input);
+
+		for (n, value) in output.iter().enumerate() {
+			let expected = 1.0 + 0.5 * (2.0 * PI as Precision * 3.0 * n as Precision / 1000.0).cos();
+			assert!((value - expected).abs() < 1e-3);
+		}
+	}
+
+	#[test]
+	fn frequency() {
+		let input  = tone(1000, 440.0, 8000.0);
+		let output = super::frequency(&*input, 8000);
+
+		assert_eq!(output.len(), 999);
+
+		for &value in &output {
+			assert!((value - 440.0).abs() < 0.5, "{}", value);
+		}
+
+		let phase = super::phase(&*input);
+		assert!((phase[999] - phase[0] - 2.0 * PI as Precision * 440.0 * 999.0 / 8000.0).abs() < 1e-2);
+	}
+}
@@ -0,0 +1,384 @@
+use std::f64::consts::PI;
+use num;
+use strided::Strided;
+
+use {Precision, Sample, forward_in};
+use window::{self, Function, Symmetry};
+use spectrum::{self, bandwidth};
+use spectrum::average::{Mel, Formula, Normalization};
+use super::frames;
+
+/// The parameters of the pipeline, the defaults with a `Rectangular` window
+/// give the same coefficients as `mfcc` from `python_speech_features` with its
+/// default arguments: 25 ms frames every 10 ms at 16 kHz, 26 HTK mel filters
+/// and 13 liftered coefficients with the log energy in place of the first one.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct Parameters {
+	/// The sample rate of the input.
+	pub rate: u32,
+
+	/// The length of a frame in samples.
+	pub frame: usize,
+
+	/// The distance between the start of two frames in samples.
+	pub hop: usize,
+
+	/// The size of the FFT, frames are zero-padded to it.
+	pub size: usize,
+
+	/// The pre-emphasis coefficient, `0.0` disables it.
+	pub emphasis: Precision,
+
+	/// The number of mel filters.
+	pub filters: usize,
+
+	/// The lowest frequency of the filterbank.
+	pub min: u32,
+
+	/// The highest frequency of the filterbank.
+	pub max: u32,
+
+	/// The mel formula.
+	pub formula: Formula,
+
+	/// The normalization of the filters.
+	pub normalization: Normalization,
+
+	/// The number of cepstral coefficients to keep.
+	pub coefficients: usize,
+
+	/// The cepstral lifter, `0` disables it.
+	pub lifter: usize,
+
+	/// Replaces the first coefficient with the log of the frame energy.
+	pub energy: bool,
+
+	/// The number of delta orders to append, `1` appends deltas and `2` also
+	/// appends delta-deltas.
+	pub deltas: usize,
+
+	/// The number of frames on each side used to compute the deltas.
+	pub width: usize,
+}
+
+impl Default for Parameters {
+	fn default() -> Self {
+		Parameters {
+			rate:          16_000,
+			frame:         400,
+			hop:           160,
+			size:          512,
+			emphasis:      0.97,
+			filters:       26,
+			min:           0,
+			max:           8_000,
+			formula:       Formula::Htk,
+			normalization: Normalization::None,
+			coefficients:  13,
+			lifter:        22,
+			energy:        true,
+			deltas:        0,
+			width:         2,
+		}
+	}
+}
+
+/// Computes the coefficients for every frame of the given input, the frames
+/// are multiplied by the given window function.
+///
+/// Every frame contains the cepstral coefficients followed by the requested
+/// deltas.
+///
+/// Panics if the parameters are inconsistent.
+pub fn compute<F, S, I>(parameters: &Parameters, input: I) -> Vec<Vec<Precision>>
+	where F: Function + 'static,
+	      S: Sample,
+	      I: Strided<Elem=S>
+{
+	let input = input.as_stride();
+	let p     = parameters;
+
+	assert!(p.frame > 0 && p.frame <= p.size, "the frame must fit in the FFT");
+	assert!(p.hop > 0, "the hop must be at least one sample");
+	assert!(p.coefficients > 0 || !p.energy, "the energy replaces the first coefficient");
+	assert!(p.coefficients <= p.filters, "there are more coefficients than filters");
+	assert!(p.min < p.max && p.max <= p.rate / 2, "the filterbank must be below Nyquist");
+	assert!(p.deltas == 0 || p.width > 0, "the deltas need a width of at least one frame");
+
+	// pre-emphasis
+	let mut emphasized = Vec::with_capacity(input.len());
+	let mut previous   = 0.0;
+
+	for sample in input.iter() {
+		let sample = sample.normalize();
+
+		emphasized.push(sample - p.emphasis * previous);
+		previous = sample;
+	}
+
+	let     window   = window::generate::<F, Precision, _>(.., Symmetry::Symmetric, p.frame);
+	let     bank     = filterbank(p);
+	let mut frame    = vec![0.0; p.size];
+	let mut bins     = vec![num::Complex::new(0.0, 0.0); p.size];
+	let mut output   = Vec::new();
+
+	for index in 0 .. frames(emphasized.len(), p.frame, p.hop) {
+		let offset = index * p.hop;
+
+		// zero-padded windowed frame
+		for value in frame.iter_mut() {
+			*value = 0.0;
+		}
+
+		for (i, value) in frame[.. p.frame].iter_mut().enumerate() {
+			if let Some(&sample) = emphasized.get(offset + i) {
+				*value = sample;
+			}
+		}
+
+		window.apply_on(&mut frame[.. p.frame]);
+		forward_in(&*frame, &mut *bins);
+
+		// power spectrum
+		let mut power = spectrum::compute(&*bins);
+
+		for value in power.iter_mut() {
+			*value = *value * *value / p.size as Precision;
+		}
+
+		let energy   = power.iter().sum::<Precision>();
+		let energies = bank.iter().map(|weights|
+			logarithm(weights.iter().zip(power.iter()).map(|(w, v)| w * v).sum())
+		).collect::<Vec<_>>();
+
+		let mut coefficients = dct(&energies);
+		coefficients.truncate(p.coefficients);
+
+		if p.lifter > 0 {
+			let lifter = p.lifter as Precision;
+
+			for (n, value) in coefficients.iter_mut().enumerate() {
+				*value *= 1.0 + (lifter / 2.0) * (PI as Precision * n as Precision / lifter).sin();
+			}
+		}
+
+		if p.energy {
+			coefficients[0] = logarithm(energy);
+		}
+
+		output.push(coefficients);
+	}
+
+	let mut deltas = Vec::new();
+
+	for order in 0 .. p.deltas {
+		let previous = if order == 0 { &output } else { &deltas[order - 1] };
+		let current  = delta(previous, p.width);
+
+		deltas.push(current);
+	}
+
+	for deltas in &deltas {
+		for (frame, delta) in output.iter_mut().zip(deltas.iter()) {
+			frame.extend_from_slice(delta);
+		}
+	}
+
+	output
+}
+
+/// Computes the weights of the mel filters over the bins of the spectrum.
+///
+/// The edges are rounded down to bins of a spectrum one bin longer than the
+/// FFT, like `python_speech_features` does.
+fn filterbank(p: &Parameters) -> Vec<Vec<Precision>> {
+	let bins      = p.size / 2 + 1;
+	let bandwidth = bandwidth(p.size, p.rate);
+	let edges     = Mel::edges(&(p.rate, p.filters, p.min, p.max, p.formula, p.normalization)).into_iter()
+		.map(|hz| ((p.size + 1) as f64 * hz as f64 / p.rate as f64).floor() as usize)
+		.collect::<Vec<_>>();
+
+	(0 .. p.filters).map(|filter| {
+		let (low, center, high) = (edges[filter], edges[filter + 1], edges[filter + 2]);
+		let mut weights         = vec![0.0; bins];
+
+		for (bin, weight) in weights.iter_mut().enumerate().take(center).skip(low) {
+			*weight = (bin - low) as Precision / (center - low) as Precision;
+		}
+
+		for (bin, weight) in weights.iter_mut().enumerate().take(high).skip(center) {
+			*weight = (high - bin) as Precision / (high - center) as Precision;
+		}
+
+		let area = weights.iter().sum::<Precision>() * bandwidth;
+
+		if p.normalization == Normalization::Area && area > 0.0 {
+			for weight in weights.iter_mut() {
+				*weight /= area;
+			}
+		}
+
+		weights
+	}).collect()
+}
+
+/// The natural logarithm with only exact zeros floored, like
+/// `python_speech_features` does.
+#[inline]
+fn logarithm(value: Precision) -> Precision {
+	if value == 0.0 {
+		(f64::EPSILON as Precision).ln()
+	}
+	else {
+		value.ln()
+	}
+}
+
+/// Computes the deltas of the given features using a regression over the
+/// given number of frames on each side, the edges are padded by repeating the
+/// first and last frames.
+///
+/// Panics if the width is zero.
+pub fn delta(features: &[Vec<Precision>], width: usize) -> Vec<Vec<Precision>> {
+	assert!(width > 0, "the deltas need a width of at least one frame");
+
+	let length      = features.len();
+	let denominator = 2.0 * (1 ..= width).map(|n| (n * n) as Precision).sum::<Precision>();
+
+	(0 .. length).map(|t| {
+		let mut result = vec![0.0; features[t].len()];
+
+		for n in 1 ..= width {
+			let next     = &features[(t + n).min(length - 1)];
+			let previous = &features[t.saturating_sub(n)];
+
+			for (i, value) in result.iter_mut().enumerate() {
+				*value += n as Precision * (next[i] - previous[i]);
+			}
+		}
+
+		for value in result.iter_mut() {
+			*value /= denominator;
+		}
+
+		result
+	}).collect()
+}
+
+/// Computes the orthonormal DCT-II of the given input.
+fn dct(input: &[Precision]) -> Vec<Precision> {
+	let length = input.len() as Precision;
+
+	(0 .. input.len()).map(|k| {
+		let scale = if k == 0 {
+			(1.0 / length).sqrt()
+		}
+		else {
+			(2.0 / length).sqrt()
+		};
+
+		scale * input.iter().enumerate().map(|(n, value)|
+			value * (PI as Precision * k as Precision * (2 * n + 1) as Precision / (2.0 * length)).cos()
+		).sum::<Precision>()
+	}).collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use std::f64::consts::PI;
+	use super::Parameters;
+	use window::{Hamming, Rectangular};
+	use Precision;
+
+	macro_rules! fix {
+		($a:expr) => (
+			if $a == "-0.000" {
+				"0.000".to_owned()
+			}
+			else {
+				$a
+			}
+		)
+	}
+
+	macro_rules! assert_approx_eq {
+		($a:expr, $b:expr) => (
+			assert_eq!(fix!(format!("{:.3}", $a)), fix!(format!("{:.3}", $b)));
+		)
+	}
+
+	#[test]
+	fn dct() {
+		let output = super::dct(&[1.0, 1.0, 1.0, 1.0]);
+
+		assert_approx_eq!(output[0], 2.0);
+		assert_approx_eq!(output[1], 0.0);
+		assert_approx_eq!(output[2], 0.0);
+		assert_approx_eq!(output[3], 0.0);
+	}
+
+	#[test]
+	fn delta() {
+		let features = (0 .. 10).map(|i| vec![i as Precision, 1.0]).collect::<Vec<_>>();
+		let output   = super::delta(&features, 2);
+
+		assert_approx_eq!(output[5][0], 1.0);
+		assert_approx_eq!(output[5][1], 0.0);
+	}
+
+	#[test]
+	fn compute() {
+		let parameters = Parameters { deltas: 2, .. Parameters::default() };
+		let input      = (0 .. 16_000).map(|i| (i as Precision * 0.1).sin()).collect::<Vec<_>>();
+		let output     = super::compute::<Hamming, _, _>(&parameters, &*input);
+
+		assert_eq!(output.len(), 99);
+
+		for frame in &output {
+			assert_eq!(frame.len(), 39);
+			assert!(frame.iter().all(|v| v.is_finite()));
+		}
+	}
+
+	#[test]
+	fn reference() {
+		let input = (0 .. 1200).map(|n| {
+			let t = n as f64 / 16_000.0;
+
+			((2.0 * PI * 440.0 * t).sin()
+				+ 0.5 * (2.0 * PI * 1250.0 * t + 0.3).sin()
+				+ 0.25 * (2.0 * PI * 3100.0 * t).sin()) as Precision
+		}).collect::<Vec<_>>();
+
+		let output = super::compute::<Rectangular, _, _>(&Parameters::default(), &*input);
+
+		// `python_speech_features.mfcc` with its default arguments, in double
+		// precision
+		let expected = [
+			(0, [2.8185, 4.8387, -21.4909, -2.8736, -40.2400, -7.5559, 2.2360, -72.6260, -24.2084, 47.1664, 13.7506, 22.2304, 12.4051]),
+			(5, [2.8072, 17.3822, -36.2228, 14.6156, -42.4050, -5.1889, 5.8945, -61.0651, -24.7152, 49.8998, 13.7026, 28.4952, 11.4827]),
+		];
+
+		assert_eq!(output.len(), 6);
+
+		for &(index, ref expected) in &expected {
+			for (a, b) in output[index].iter().zip(expected.iter()) {
+				assert!((a - b).abs() < 1e-3, "{} {} {}", index, a, b);
+			}
+		}
+	}
+
+	#[test]
+	#[should_panic(expected = "the energy replaces the first coefficient")]
+	fn energy() {
+		let parameters = Parameters { coefficients: 0, .. Parameters::default() };
+		super::compute::<Rectangular, _, _>(&parameters, &[0.0; 400][..]);
+	}
+
+	#[test]
+	#[should_panic(expected = "the deltas need a width of at least one frame")]
+	fn width() {
+		let parameters = Parameters { deltas: 1, width: 0, .. Parameters::default() };
+		super::compute::<Rectangular, _, _>(&parameters, &[0.0; 400][..]);
+	}
+}
@@ -0,0 +1,36 @@
+/// Mel-frequency cepstral coefficients.
+pub mod mfcc;
+
+/// Constant-Q transform.
+pub mod cqt;
+
+/// Chroma and pitch-class profiles.
+pub mod chroma;
+
+/// Spectral shape descriptors.
+pub mod descriptors;
+
+/// Onset detection.
+pub mod onset;
+
+/// Fundamental frequency estimation.
+pub mod pitch;
+
+/// The Hilbert transform and the analytic signal.
+pub mod hilbert;
+
+/// Real, power and complex cepstrum.
+pub mod cepstrum;
+
+/// Returns the number of frames of the given length every `hop` samples needed
+/// to cover the given input length, the last frame is zero-padded.
+fn frames(length: usize, frame: usize, hop: usize) -> usize {
+	debug_assert!(hop > 0);
+
+	if length <= frame {
+		1
+	}
+	else {
+		1 + (length - frame).div_ceil(hop)
+	}
+}
@@ -0,0 +1,309 @@
+use std::f64::consts::PI;
+use strided::Strided;
+
+use {Precision, Complex};
+
+/// The onset detection functions.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Detection {
+	/// Half-wave rectified spectral flux, the sum of the magnitude increases.
+	Flux,
+
+	/// High-frequency content, the energy weighted by the bin index.
+	Hfc,
+
+	/// Complex-domain distance from the magnitude and phase predicted by the
+	/// previous two frames.
+	ComplexDomain,
+
+	/// Mean absolute deviation of the phase from the one predicted by the
+	/// previous two frames.
+	PhaseDeviation,
+}
+
+/// The parameters of the peak picking.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct Picking {
+	/// The sample rate of the signal the frames were computed from.
+	pub rate: u32,
+
+	/// The distance between the start of two frames in samples.
+	pub hop: usize,
+
+	/// The number of frames before and after a peak it must be the maximum of.
+	pub max: (usize, usize),
+
+	/// The number of frames before and after a peak used for the adaptive
+	/// threshold.
+	pub average: (usize, usize),
+
+	/// The fixed part of the threshold.
+	pub delta: Precision,
+
+	/// The multiplier of the local median in the threshold.
+	pub lambda: Precision,
+
+	/// The minimum number of frames between two onsets.
+	pub wait: usize,
+}
+
+impl Default for Picking {
+	fn default() -> Self {
+		Picking {
+			rate:    44_100,
+			hop:     512,
+			max:     (3, 3),
+			average: (10, 10),
+			delta:   0.05,
+			lambda:  1.0,
+			wait:    3,
+		}
+	}
+}
+
+/// Wraps the given phase to `-π ..= π`.
+#[inline]
+fn wrap(phase: Precision) -> Precision {
+	let pi2 = 2.0 * PI as Precision;
+
+	phase - pi2 * ((phase + PI as Precision) / pi2).floor()
+}
+
+/// Computes the half-wave rectified spectral flux between two consecutive
+/// frames.
+pub fn flux<C, P, I>(previous: P, current: I) -> Precision
+	where C: Complex,
+	      P: Strided<Elem=C>,
+	      I: Strided<Elem=C>
+{
+	previous.as_stride().iter().zip(current.as_stride().iter())
+		.map(|(p, c)| (c.to_num().norm() - p.to_num().norm()).max(0.0))
+		.sum()
+}
+
+/// Computes the high-frequency content of a frame.
+pub fn hfc<C, I>(current: I) -> Precision
+	where C: Complex,
+	      I: Strided<Elem=C>
+{
+	current.as_stride().iter().enumerate()
+		.map(|(k, c)| k as Precision * c.to_num().norm_sqr())
+		.sum()
+}
+
+/// Computes the complex-domain distance of a frame from the prediction of the
+/// previous two frames.
+pub fn complex_domain<C, B, P, I>(before: B, previous: P, current: I) -> Precision
+	where C: Complex,
+	      B: Strided<Elem=C>,
+	      P: Strided<Elem=C>,
+	      I: Strided<Elem=C>
+{
+	let before   = before.as_stride();
+	let previous = previous.as_stride();
+	let current  = current.as_stride();
+
+	before.iter().zip(previous.iter()).zip(current.iter()).map(|((b, p), c)| {
+		let (magnitude, phase) = p.to_num().to_polar();
+		let target = ::num::Complex::from_polar(&magnitude, &(2.0 * phase - b.to_num().arg()));
+
+		(c.to_num() - target).norm()
+	}).sum()
+}
+
+/// Computes the mean absolute phase deviation of a frame from the prediction
+/// of the previous two frames.
+pub fn phase_deviation<C, B, P, I>(before: B, previous: P, current: I) -> Precision
+	where C: Complex,
+	      B: Strided<Elem=C>,
+	      P: Strided<Elem=C>,
+	      I: Strided<Elem=C>
+{
+	let before   = before.as_stride();
+	let previous = previous.as_stride();
+	let current  = current.as_stride();
+
+	let total = before.iter().zip(previous.iter()).zip(current.iter()).map(|((b, p), c)|
+		wrap(c.to_num().arg() - 2.0 * p.to_num().arg() + b.to_num().arg()).abs()
+	).sum::<Precision>();
+
+	total / current.len() as Precision
+}
+
+/// Computes the onset detection function over consecutive STFT frames, the
+/// frames before the first are treated as silent.
+pub fn detect<C, F>(detection: Detection, frames: &[F]) -> Vec<Precision>
+	where C: Complex,
+	      F: Strided<Elem=C>
+{
+	let length = frames.first().map(|f| f.as_stride().len()).unwrap_or(0);
+	let silent = vec![C::zero(); length];
+
+	(0 .. frames.len()).map(|t| {
+		let current  = frames[t].as_stride();
+		let previous = if t >= 1 { frames[t - 1].as_stride() } else { silent.as_stride() };
+		let before   = if t >= 2 { frames[t - 2].as_stride() } else { silent.as_stride() };
+
+		match detection {
+			Detection::Flux           => flux(previous, current),
+			Detection::Hfc            => hfc(current),
+			Detection::ComplexDomain  => complex_domain(before, previous, current),
+			Detection::PhaseDeviation => phase_deviation(before, previous, current),
+		}
+	}).collect()
+}
+
+/// Picks the peaks of the given onset detection function and returns their
+/// indices.
+///
+/// A frame is a peak if it is the maximum within `max` frames, it's above the
+/// median within `average` frames times `lambda` plus `delta`, and it's at
+/// least `wait` frames after the previous peak.
+pub fn peaks(picking: &Picking, function: &[Precision]) -> Vec<usize> {
+	let     length = function.len();
+	let mut output = Vec::<usize>::new();
+	let mut window = Vec::new();
+
+	for t in 0 .. length {
+		let value = function[t];
+
+		let max = function[t.saturating_sub(picking.max.0) .. (t + picking.max.1 + 1).min(length)]
+			.iter().fold(Precision::MIN, |max, &v| max.max(v));
+
+		// NaNs from silent frames are never peaks
+		if value.is_nan() || value < max {
+			continue;
+		}
+
+		window.clear();
+		window.extend_from_slice(&function[t.saturating_sub(picking.average.0) .. (t + picking.average.1 + 1).min(length)]);
+		window.sort_by(|a, b| a.total_cmp(b));
+
+		let median = if window.len() % 2 == 1 {
+			window[window.len() / 2]
+		}
+		else {
+			(window[window.len() / 2 - 1] + window[window.len() / 2]) / 2.0
+		};
+
+		if value < picking.delta + picking.lambda * median {
+			continue;
+		}
+
+		if let Some(&last) = output.last() {
+			if t - last < picking.wait {
+				continue;
+			}
+		}
+
+		output.push(t);
+	}
+
+	output
+}
+
+/// Picks the peaks of the given onset detection function and returns their
+/// times in seconds.
+pub fn pick(picking: &Picking, function: &[Precision]) -> Vec<Precision> {
+	peaks(picking, function).into_iter()
+		.map(|t| (t * picking.hop) as Precision / picking.rate as Precision)
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use std::f64::consts::FRAC_PI_2;
+	use num::Complex;
+	use super::{Detection, Picking};
+	use Precision;
+
+	fn frames() -> Vec<Vec<Complex<Precision>>> {
+		// silence, then a steady tone starting at frame 5
+		(0 .. 10).map(|t| {
+			(0 .. 8).map(|k| {
+				if t >= 5 {
+					Complex::from_polar(&1.0, &(k as Precision * t as Precision))
+				}
+				else {
+					Complex::new(0.0, 0.0)
+				}
+			}).collect()
+		}).collect()
+	}
+
+	#[test]
+	fn flux() {
+		let function = super::detect(Detection::Flux, &frames());
+
+		assert_eq!(function[4], 0.0);
+		assert_eq!(function[5], 8.0);
+		assert_eq!(function[6], 0.0);
+	}
+
+	#[test]
+	fn hfc() {
+		let mut frame = vec![Complex::new(0.0, 0.0); 8];
+
+		// DC doesn't count, higher bins weigh more
+		frame[0] = Complex::new(1.0, 0.0);
+		assert_eq!(super::hfc(&*frame), 0.0);
+
+		frame[1] = Complex::new(0.0, 2.0);
+		assert_eq!(super::hfc(&*frame), 4.0);
+
+		frame[1] = Complex::new(0.0, 0.0);
+		frame[3] = Complex::new(0.0, 2.0);
+		assert_eq!(super::hfc(&*frame), 12.0);
+	}
+
+	#[test]
+	fn phase_deviation() {
+		let mut frames = frames();
+		let     steady = super::detect(Detection::PhaseDeviation, &frames);
+
+		// a steady tone has a linear phase
+		assert!(steady[7 ..].iter().all(|&d| d < 1e-3));
+
+		// shift the phase by a quarter turn from frame 7 on
+		for frame in &mut frames[7 ..] {
+			for value in frame.iter_mut() {
+				*value *= Complex::new(0.0, 1.0);
+			}
+		}
+
+		let jump = super::detect(Detection::PhaseDeviation, &frames);
+		let half = FRAC_PI_2 as Precision;
+
+		assert!((jump[7] - half).abs() < 1e-3);
+		assert!((jump[8] - half).abs() < 1e-3);
+		assert!(jump[9] < 1e-3);
+	}
+
+	#[test]
+	fn complex_domain() {
+		let function = super::detect(Detection::ComplexDomain, &frames());
+
+		// the tone is perfectly predictable after two frames
+		assert!(function[5] > 7.9);
+		assert!(function[7] < 1e-3);
+	}
+
+	#[test]
+	fn pick() {
+		let mut function = vec![0.0; 100];
+		function[20] = 1.0;
+		function[22] = 0.8;
+		function[60] = 0.5;
+
+		let picking = Picking { rate: 1000, hop: 10, wait: 5, .. Picking::default() };
+
+		assert_eq!(super::peaks(&picking, &function), vec![20, 60]);
+		assert_eq!(super::pick(&picking, &function), vec![0.2, 0.6]);
+
+		// normalized functions are NaN over silence
+		function[40] = Precision::NAN;
+		function[62] = Precision::NAN;
+
+		assert_eq!(super::peaks(&picking, &function), vec![20, 60]);
+	}
+}
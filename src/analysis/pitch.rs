@@ -0,0 +1,537 @@
+use num::{self, Zero};
+use strided::{Strided, Stride};
+
+use {Precision, Sample, forward, forward_in, inverse};
+use window::{self, Hann, Symmetry};
+use spectrum::{self, bandwidth};
+use spectrum::peak::parabolic;
+use super::frames;
+
+/// The resolution of the pYIN pitch states in cents.
+const RESOLUTION: Precision = 20.0;
+
+/// The largest pitch change between two frames in pYIN pitch states.
+const JUMP: usize = 25;
+
+/// The probability of switching between voiced and unvoiced in pYIN.
+const SWITCH: Precision = 0.01;
+
+/// The pitch estimate of a single frame.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct Estimate {
+	/// The fundamental frequency in hertz, the best guess even when unvoiced and
+	/// `0.0` for silent frames.
+	pub frequency: Precision,
+
+	/// The confidence of the estimate, between `0.0` and `1.0`.
+	pub confidence: Precision,
+
+	/// Whether the frame is considered voiced.
+	pub voiced: bool,
+}
+
+impl Estimate {
+	fn silent() -> Self {
+		Estimate {
+			frequency:  0.0,
+			confidence: 0.0,
+			voiced:     false,
+		}
+	}
+}
+
+/// The parameters of the estimators.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct Parameters {
+	/// The sample rate of the input.
+	pub rate: u32,
+
+	/// The length of a frame in samples, it must be longer than the period of
+	/// the lowest frequency.
+	pub frame: usize,
+
+	/// The distance between the start of two frames in samples.
+	pub hop: usize,
+
+	/// The size of the FFT for the harmonic product spectrum, frames are
+	/// zero-padded to it.
+	pub size: usize,
+
+	/// The lowest detectable frequency.
+	pub min: Precision,
+
+	/// The highest detectable frequency.
+	pub max: Precision,
+
+	/// The YIN threshold on the cumulative mean normalized difference.
+	pub threshold: Precision,
+
+	/// The number of harmonics of the harmonic product spectrum.
+	pub harmonics: usize,
+
+	/// The harmonicity above which the harmonic product spectrum considers a
+	/// frame voiced.
+	pub voicing: Precision,
+}
+
+impl Default for Parameters {
+	fn default() -> Self {
+		Parameters {
+			rate:      44_100,
+			frame:     2048,
+			hop:       512,
+			size:      8192,
+			min:       65.0,
+			max:       2093.0,
+			threshold: 0.1,
+			harmonics: 5,
+			voicing:   0.5,
+		}
+	}
+}
+
+/// Estimates the pitch of every frame of the given input with YIN by de
+/// Cheveigné and Kawahara.
+///
+/// The confidence is one minus the cumulative mean normalized difference at
+/// the chosen lag, a frame is voiced if the difference is below the threshold.
+pub fn yin<S, I>(parameters: &Parameters, input: I) -> Vec<Estimate>
+	where S: Sample,
+	      I: Strided<Elem=S>
+{
+	let     p           = parameters;
+	let     (low, high) = lags(p);
+	let mut frame       = vec![0.0; p.frame];
+
+	(0 .. frames(input.as_stride().len(), p.frame, p.hop)).map(|index| {
+		extract(input.as_stride(), index * p.hop, &mut frame);
+
+		let difference = normalized(&frame, high);
+
+		if difference[1 ..].iter().all(|&d| d == 1.0) {
+			return Estimate::silent();
+		}
+
+		let (lag, voiced) = match trough(&difference, low, high, p.threshold) {
+			Some(lag) => (lag, true),
+			None      => (minimum(&difference, low, high), false),
+		};
+
+		Estimate {
+			frequency:  p.rate as Precision / refine(&difference, lag),
+			confidence: (1.0 - difference[lag]).clamp(0.0, 1.0),
+			voiced,
+		}
+	}).collect()
+}
+
+/// Estimates the pitch of every frame of the given input with probabilistic
+/// YIN by Mauch and Dixon.
+///
+/// Every trough of the cumulative mean normalized difference gets the
+/// probability of being picked under a Beta(2, 18) distribution of
+/// thresholds, then a hidden Markov model with voiced and unvoiced pitch
+/// states is decoded with Viterbi to get a smooth track.
+///
+/// The confidence is the voicing probability of the frame.
+pub fn pyin<S, I>(parameters: &Parameters, input: I) -> Vec<Estimate>
+	where S: Sample,
+	      I: Strided<Elem=S>
+{
+	let     p           = parameters;
+	let     (low, high) = lags(p);
+	let mut frame       = vec![0.0; p.frame];
+
+	// Beta(2, 18) distribution over the thresholds
+	let thresholds = (1 .. 101).map(|i| i as Precision / 100.0).collect::<Vec<_>>();
+	let mut prior  = thresholds.iter().map(|&t| t * (1.0 - t).powi(17)).collect::<Vec<_>>();
+	let total      = prior.iter().sum::<Precision>();
+
+	for value in prior.iter_mut() {
+		*value /= total;
+	}
+
+	// candidates as frequency and probability for every frame
+	let candidates = (0 .. frames(input.as_stride().len(), p.frame, p.hop)).map(|index| {
+		extract(input.as_stride(), index * p.hop, &mut frame);
+
+		let difference = normalized(&frame, high);
+		let troughs    = (low .. high + 1)
+			.filter(|&lag| difference[lag] < difference[lag - 1] &&
+				(lag == high || difference[lag] <= difference[lag + 1]))
+			.collect::<Vec<_>>();
+
+		if troughs.is_empty() {
+			return Vec::new();
+		}
+
+		let global          = minimum(&difference, low, high);
+		let mut probability = vec![0.0; troughs.len()];
+
+		for (&threshold, &weight) in thresholds.iter().zip(prior.iter()) {
+			match troughs.iter().position(|&lag| difference[lag] < threshold) {
+				Some(index) =>
+					probability[index] += weight,
+
+				None => if let Some(index) = troughs.iter().position(|&lag| lag == global) {
+					probability[index] += weight * 0.01;
+				},
+			}
+		}
+
+		troughs.iter().zip(probability)
+			.filter(|&(_, probability)| probability > 0.0)
+			.map(|(&lag, probability)| (p.rate as Precision / refine(&difference, lag), probability))
+			.collect()
+	}).collect::<Vec<Vec<_>>>();
+
+	if candidates.is_empty() {
+		return Vec::new();
+	}
+
+	let bins   = (1200.0 * (p.max / p.min).log2() / RESOLUTION).ceil() as usize + 1;
+	let state  = |frequency: Precision|
+		((1200.0 * (frequency / p.min).log2() / RESOLUTION).round().max(0.0) as usize).min(bins - 1);
+	let center = |bin: usize| p.min * (2.0 as Precision).powf(bin as Precision * RESOLUTION / 1200.0);
+
+	// observation log-likelihoods, voiced states first then unvoiced
+	let observe = |candidates: &[(Precision, Precision)]| {
+		let mut output = vec![0.0 as Precision; bins * 2];
+		let     voiced = candidates.iter().map(|&(_, p)| p).sum::<Precision>().min(1.0);
+
+		for &(frequency, probability) in candidates {
+			output[state(frequency)] += probability;
+		}
+
+		for value in output[bins ..].iter_mut() {
+			*value = (1.0 - voiced) / bins as Precision;
+		}
+
+		for value in output.iter_mut() {
+			*value = value.max(Precision::MIN_POSITIVE).ln();
+		}
+
+		output
+	};
+
+	// triangular pitch transitions
+	let weights = (0 .. JUMP + 1).map(|d| (JUMP + 1 - d) as Precision).collect::<Vec<_>>();
+	let norm    = weights[0] + 2.0 * weights[1 ..].iter().sum::<Precision>();
+	let stay    = (1.0 - SWITCH).ln();
+	let switch  = SWITCH.ln();
+
+	let mut score = observe(&candidates[0]);
+	let mut paths = Vec::with_capacity(candidates.len());
+
+	for frame in &candidates[1 ..] {
+		let     observation = observe(frame);
+		let mut next        = vec![Precision::NEG_INFINITY; bins * 2];
+		let mut path        = vec![0; bins * 2];
+
+		for to in 0 .. bins * 2 {
+			let bin   = to % bins;
+			let start = bin.saturating_sub(JUMP);
+			let end   = (bin + JUMP).min(bins - 1);
+
+			for from in start .. end + 1 {
+				let pitch = (weights[bin.max(from) - bin.min(from)] / norm).ln();
+
+				for &(offset, voicing) in &[(0, to < bins), (bins, to >= bins)] {
+					let value = score[from + offset] + pitch + if voicing { stay } else { switch };
+
+					if value > next[to] {
+						next[to] = value;
+						path[to] = from + offset;
+					}
+				}
+			}
+
+			next[to] += observation[to];
+		}
+
+		score = next;
+		paths.push(path);
+	}
+
+	// backtrack
+	let mut states = vec![0; candidates.len()];
+	states[candidates.len() - 1] = (0 .. bins * 2)
+		.max_by(|&a, &b| score[a].total_cmp(&score[b])).unwrap();
+
+	for (index, path) in paths.iter().enumerate().rev() {
+		states[index] = path[states[index + 1]];
+	}
+
+	states.iter().zip(candidates.iter()).map(|(&s, candidates)| {
+		if candidates.is_empty() {
+			return Estimate::silent();
+		}
+
+		let bin        = s % bins;
+		let confidence = candidates.iter().map(|&(_, p)| p).sum::<Precision>().min(1.0);
+
+		// prefer the exact candidate within the state over its center
+		let frequency = candidates.iter()
+			.filter(|&&(frequency, _)| state(frequency) == bin)
+			.max_by(|a, b| a.1.total_cmp(&b.1))
+			.map(|&(frequency, _)| frequency)
+			.unwrap_or_else(|| center(bin));
+
+		Estimate {
+			frequency,
+			confidence,
+			voiced: s < bins,
+		}
+	}).collect()
+}
+
+/// Estimates the pitch of every frame of the given input with the harmonic
+/// product spectrum.
+///
+/// The magnitude spectrum is multiplied with its downsampled copies, so the
+/// harmonics line up on the fundamental. The confidence is the harmonicity,
+/// the fraction of the spectral energy at the harmonics of the estimate.
+pub fn harmonic<S, I>(parameters: &Parameters, input: I) -> Vec<Estimate>
+	where S: Sample,
+	      I: Strided<Elem=S>
+{
+	let p = parameters;
+
+	debug_assert!(p.frame <= p.size);
+	debug_assert!(p.harmonics > 0);
+
+	let     window    = window::generate::<Hann, Precision, _>(.., Symmetry::Periodic, p.frame);
+	let     bandwidth = bandwidth(p.size, p.rate);
+	let mut frame     = vec![0.0; p.size];
+	let mut bins      = vec![num::Complex::<Precision>::zero(); p.size];
+
+	let low  = ((p.min / bandwidth).floor() as usize).max(1);
+	let high = ((p.max / bandwidth).ceil() as usize).min(p.size / 2 / p.harmonics);
+
+	debug_assert!(low < high);
+
+	(0 .. frames(input.as_stride().len(), p.frame, p.hop)).map(|index| {
+		for value in frame.iter_mut() {
+			*value = 0.0;
+		}
+
+		extract(input.as_stride(), index * p.hop, &mut frame[.. p.frame]);
+		window.apply_on(&mut frame[.. p.frame]);
+		forward_in(&*frame, &mut *bins);
+
+		let magnitude = spectrum::compute(&*bins);
+		let energy    = magnitude.iter().map(|m| m * m).sum::<Precision>();
+
+		if energy == 0.0 {
+			return Estimate::silent();
+		}
+
+		// in the log domain to avoid underflows, with a -60 dB floor so missing
+		// harmonics don't dominate
+		let floor   = magnitude.iter().fold(0.0, |max: Precision, &m| max.max(m)) * 1e-3;
+		let product = (0 .. high + 2).map(|bin|
+			(1 .. p.harmonics + 1)
+				.map(|h| magnitude.get(bin * h).cloned().unwrap_or(0.0).max(floor).ln())
+				.sum::<Precision>()
+		).collect::<Vec<_>>();
+
+		let peak = (low .. high + 1)
+			.max_by(|&a, &b| product[a].total_cmp(&product[b])).unwrap();
+
+		let frequency = (peak as Precision + offset(product[peak - 1], product[peak], product[peak + 1])) * bandwidth;
+
+		// the main lobe of the Hann window spans two bins of the unpadded frame
+		// on each side, without overlapping the next harmonic
+		let lobe      = (2 * p.size / p.frame).min((frequency / bandwidth / 2.0) as usize);
+		let harmonics = (1 .. p.harmonics + 1).map(|h| {
+			let bin = (h as Precision * frequency / bandwidth).round() as usize;
+
+			(bin.saturating_sub(lobe) .. (bin + lobe + 1).min(magnitude.len()))
+				.map(|bin| magnitude[bin] * magnitude[bin])
+				.sum::<Precision>()
+		}).sum::<Precision>();
+
+		let confidence = (harmonics / energy).min(1.0);
+
+		Estimate {
+			frequency,
+			confidence,
+			voiced: confidence >= p.voicing,
+		}
+	}).collect()
+}
+
+/// The range of lags for the given frequency range.
+fn lags(p: &Parameters) -> (usize, usize) {
+	let low  = ((p.rate as Precision / p.max).floor() as usize).max(2);
+	let high = (p.rate as Precision / p.min).ceil() as usize;
+
+	debug_assert!(low < high);
+	debug_assert!(high + 1 < p.frame, "the frame is shorter than the longest period");
+
+	(low, high)
+}
+
+/// Copies the normalized samples starting at the given offset into the frame,
+/// the samples past the end of the input are zero.
+fn extract<S: Sample>(input: Stride<S>, offset: usize, frame: &mut [Precision]) {
+	for (i, value) in frame.iter_mut().enumerate() {
+		*value = input.get(offset + i).map(|s| s.normalize()).unwrap_or(0.0);
+	}
+}
+
+/// Computes the cumulative mean normalized difference function up to the
+/// given lag, the difference is integrated over the frame minus the lag and
+/// computed through the autocorrelation in the frequency domain.
+fn normalized(frame: &[Precision], lags: usize) -> Vec<Precision> {
+	let width = frame.len() - lags;
+	let size  = (frame.len() + width).next_power_of_two();
+
+	let mut head = vec![0.0; size];
+	let mut full = vec![0.0; size];
+
+	head[.. width].copy_from_slice(&frame[.. width]);
+	full[.. frame.len()].copy_from_slice(frame);
+
+	let head: Vec<num::Complex<Precision>> = forward(&*head);
+	let full: Vec<num::Complex<Precision>> = forward(&*full);
+
+	let product     = head.iter().zip(full.iter()).map(|(h, f)| h.conj() * f).collect::<Vec<_>>();
+	let correlation: Vec<num::Complex<Precision>> = inverse(&*product);
+
+	// running energy to get the energy of every shifted window
+	let mut energy = vec![0.0; frame.len() + 1];
+
+	for (i, sample) in frame.iter().enumerate() {
+		energy[i + 1] = energy[i] + sample * sample;
+	}
+
+	let mut output  = vec![1.0; lags + 1];
+	let mut running = 0.0;
+
+	for lag in 1 .. lags + 1 {
+		let difference = (energy[width] + energy[lag + width] - energy[lag]
+			- 2.0 * correlation[lag].re).max(0.0);
+
+		running += difference;
+
+		output[lag] = if running > 0.0 {
+			difference * lag as Precision / running
+		}
+		else {
+			1.0
+		};
+	}
+
+	output
+}
+
+/// Finds the first lag below the threshold, then follows it down to the
+/// bottom of its trough.
+fn trough(difference: &[Precision], low: usize, high: usize, threshold: Precision) -> Option<usize> {
+	let mut lag = (low .. high + 1).find(|&lag| difference[lag] < threshold)?;
+
+	while lag < high && difference[lag + 1] < difference[lag] {
+		lag += 1;
+	}
+
+	Some(lag)
+}
+
+/// Finds the lag with the smallest difference.
+fn minimum(difference: &[Precision], low: usize, high: usize) -> usize {
+	(low .. high + 1)
+		.min_by(|&a, &b| difference[a].total_cmp(&difference[b])).unwrap()
+}
+
+/// Refines the given lag with parabolic interpolation.
+fn refine(difference: &[Precision], lag: usize) -> Precision {
+	if lag + 1 >= difference.len() {
+		return lag as Precision;
+	}
+
+	lag as Precision + offset(difference[lag - 1], difference[lag], difference[lag + 1])
+}
+
+/// Computes the offset of the vertex of the parabola through three equally
+/// spaced points from the middle one, within half a bin.
+#[inline]
+fn offset(left: Precision, center: Precision, right: Precision) -> Precision {
+	parabolic(left, center, right).clamp(-0.5, 0.5)
+}
+
+#[cfg(test)]
+mod tests {
+	use std::f64::consts::PI;
+	use super::Parameters;
+	use Precision;
+
+	fn tone(rate: u32, frequency: Precision, length: usize) -> Vec<Precision> {
+		(0 .. length).map(|n| {
+			let phase = 2.0 * PI as Precision * frequency * n as Precision / rate as Precision;
+
+			(1 .. 4).map(|h| (h as Precision * phase).sin() / h as Precision).sum()
+		}).collect()
+	}
+
+	#[test]
+	fn yin() {
+		let input  = tone(44100, 220.0, 8192);
+		let output = super::yin(&Parameters::default(), &*input);
+
+		for estimate in &output[.. 10] {
+			assert!(estimate.voiced);
+			assert!((estimate.frequency - 220.0).abs() < 1.0);
+			assert!(estimate.confidence > 0.9);
+		}
+	}
+
+	#[test]
+	fn silence() {
+		let input  = vec![0.0 as Precision; 8192];
+		let output = super::yin(&Parameters::default(), &*input);
+
+		assert!(output.iter().all(|e| !e.voiced && e.frequency == 0.0));
+	}
+
+	#[test]
+	fn invalid() {
+		// NaNs are never picked as the minimum
+		let difference = [1.0, Precision::NAN, 0.5, 0.7, Precision::NAN];
+		assert_eq!(super::minimum(&difference, 1, 4), 2);
+
+		let mut input = tone(44100, 220.0, 8192);
+		input[3000] = Precision::NAN;
+
+		assert_eq!(super::yin(&Parameters::default(), &*input).len(), 13);
+		assert_eq!(super::harmonic(&Parameters::default(), &*input).len(), 13);
+	}
+
+	#[test]
+	fn pyin() {
+		let mut input = tone(44100, 330.0, 16384);
+		input.extend(vec![0.0; 16384]);
+
+		let output = super::pyin(&Parameters::default(), &*input);
+
+		for estimate in &output[.. 20] {
+			assert!(estimate.voiced);
+			assert!((estimate.frequency - 330.0).abs() < 1.5);
+		}
+
+		for estimate in &output[output.len() - 20 ..] {
+			assert!(!estimate.voiced);
+		}
+	}
+
+	#[test]
+	fn harmonic() {
+		let input  = tone(44100, 196.0, 16384);
+		let output = super::harmonic(&Parameters::default(), &*input);
+
+		for estimate in &output[.. 20] {
+			assert!(estimate.voiced);
+			assert!((estimate.frequency - 196.0).abs() < 2.0);
+		}
+	}
+}
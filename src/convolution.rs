@@ -0,0 +1,309 @@
+use std::ops::Range;
+use num::{self, Zero};
+use strided::{Strided, MutStrided, Stride};
+
+use {Precision, Complex, ComplexMut, SampleMut, forward_in, inverse_in};
+
+/// The length of the shorter operand up to which the convolution is computed
+/// directly instead of through the FFT.
+pub const DIRECT: usize = 32;
+
+/// The part of the convolution to output.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Mode {
+	/// The whole convolution, `N + M - 1` values.
+	Full,
+
+	/// The central part with the same length as the input, `N` values.
+	Same,
+
+	/// The part computed without the zero padding, `max(N, M) - min(N, M) + 1`
+	/// values.
+	Valid,
+}
+
+impl Mode {
+	/// The output length for the given input and kernel lengths.
+	#[inline]
+	pub fn length(&self, input: usize, kernel: usize) -> usize {
+		self.range(input, kernel).len()
+	}
+
+	/// The range of the full convolution to output.
+	fn range(&self, input: usize, kernel: usize) -> Range<usize> {
+		if input == 0 || kernel == 0 {
+			return 0 .. 0;
+		}
+
+		match *self {
+			Mode::Full =>
+				0 .. input + kernel - 1,
+
+			Mode::Same => {
+				let start = (kernel - 1) / 2;
+
+				start .. start + input
+			}
+
+			Mode::Valid => {
+				let (short, long) = if input < kernel { (input, kernel) } else { (kernel, input) };
+
+				short - 1 .. long
+			}
+		}
+	}
+}
+
+/// Convolves the given input with the given kernel and returns a vector of
+/// complex numbers.
+#[inline(always)]
+pub fn convolve<CI, CK, CO, I, K>(input: I, kernel: K, mode: Mode) -> Vec<CO>
+	where CI: Complex,
+	      CK: Complex,
+	      CO: ComplexMut,
+	      I:  Strided<Elem=CI>,
+	      K:  Strided<Elem=CK>
+{
+	let mut output = vec![CO::zero(); mode.length(input.as_stride().len(), kernel.as_stride().len())];
+	convolve_in(input, kernel, mode, &mut *output);
+
+	output
+}
+
+/// Convolves the given input with the given kernel and puts the result into
+/// the given output.
+pub fn convolve_in<CI, CK, CO, I, K, O>(input: I, kernel: K, mode: Mode, mut output: O)
+	where CI: Complex,
+	      CK: Complex,
+	      CO: ComplexMut,
+	      I:  Strided<Elem=CI>,
+	      K:  Strided<Elem=CK>,
+	      O:  MutStrided<Elem=CO>
+{
+	let kernel = kernel.as_stride().iter().map(|c| c.to_num()).collect::<Vec<_>>();
+
+	for (output, value) in output.as_stride_mut().iter_mut().zip(compute(input.as_stride(), &kernel, mode)) {
+		output.set(&value);
+	}
+}
+
+/// Convolves the given real input with the given real kernel and returns a
+/// vector of samples.
+#[inline(always)]
+pub fn convolve_real<CI, CK, SO, I, K>(input: I, kernel: K, mode: Mode) -> Vec<SO>
+	where CI: Complex,
+	      CK: Complex,
+	      SO: SampleMut,
+	      I:  Strided<Elem=CI>,
+	      K:  Strided<Elem=CK>
+{
+	let mut output = vec![SO::zero(); mode.length(input.as_stride().len(), kernel.as_stride().len())];
+	convolve_real_in(input, kernel, mode, &mut *output);
+
+	output
+}
+
+/// Convolves the given real input with the given real kernel and puts the
+/// result into the given output, the imaginary parts are dropped.
+pub fn convolve_real_in<CI, CK, SO, I, K, O>(input: I, kernel: K, mode: Mode, mut output: O)
+	where CI: Complex,
+	      CK: Complex,
+	      SO: SampleMut,
+	      I:  Strided<Elem=CI>,
+	      K:  Strided<Elem=CK>,
+	      O:  MutStrided<Elem=SO>
+{
+	let kernel = kernel.as_stride().iter().map(|c| c.to_num()).collect::<Vec<_>>();
+
+	for (output, value) in output.as_stride_mut().iter_mut().zip(compute(input.as_stride(), &kernel, mode)) {
+		output.set_normalized(value.re);
+	}
+}
+
+/// Cross-correlates the given input with the given kernel and returns a
+/// vector of complex numbers.
+///
+/// The full output starts at lag `1 - M`, the kernel is conjugated.
+#[inline(always)]
+pub fn correlate<CI, CK, CO, I, K>(input: I, kernel: K, mode: Mode) -> Vec<CO>
+	where CI: Complex,
+	      CK: Complex,
+	      CO: ComplexMut,
+	      I:  Strided<Elem=CI>,
+	      K:  Strided<Elem=CK>
+{
+	let mut output = vec![CO::zero(); mode.length(input.as_stride().len(), kernel.as_stride().len())];
+	correlate_in(input, kernel, mode, &mut *output);
+
+	output
+}
+
+/// Cross-correlates the given input with the given kernel and puts the result
+/// into the given output.
+pub fn correlate_in<CI, CK, CO, I, K, O>(input: I, kernel: K, mode: Mode, mut output: O)
+	where CI: Complex,
+	      CK: Complex,
+	      CO: ComplexMut,
+	      I:  Strided<Elem=CI>,
+	      K:  Strided<Elem=CK>,
+	      O:  MutStrided<Elem=CO>
+{
+	let kernel = reversed(kernel.as_stride());
+
+	for (output, value) in output.as_stride_mut().iter_mut().zip(compute(input.as_stride(), &kernel, mode)) {
+		output.set(&value);
+	}
+}
+
+/// Cross-correlates the given real input with the given real kernel and
+/// returns a vector of samples.
+#[inline(always)]
+pub fn correlate_real<CI, CK, SO, I, K>(input: I, kernel: K, mode: Mode) -> Vec<SO>
+	where CI: Complex,
+	      CK: Complex,
+	      SO: SampleMut,
+	      I:  Strided<Elem=CI>,
+	      K:  Strided<Elem=CK>
+{
+	let mut output = vec![SO::zero(); mode.length(input.as_stride().len(), kernel.as_stride().len())];
+	correlate_real_in(input, kernel, mode, &mut *output);
+
+	output
+}
+
+/// Cross-correlates the given real input with the given real kernel and puts
+/// the result into the given output, the imaginary parts are dropped.
+pub fn correlate_real_in<CI, CK, SO, I, K, O>(input: I, kernel: K, mode: Mode, mut output: O)
+	where CI: Complex,
+	      CK: Complex,
+	      SO: SampleMut,
+	      I:  Strided<Elem=CI>,
+	      K:  Strided<Elem=CK>,
+	      O:  MutStrided<Elem=SO>
+{
+	let kernel = reversed(kernel.as_stride());
+
+	for (output, value) in output.as_stride_mut().iter_mut().zip(compute(input.as_stride(), &kernel, mode)) {
+		output.set_normalized(value.re);
+	}
+}
+
+/// Reverses and conjugates the kernel, turning the convolution into a
+/// correlation.
+fn reversed<C: Complex>(kernel: Stride<C>) -> Vec<num::Complex<Precision>> {
+	let mut output = kernel.iter().map(|c| c.to_num().conj()).collect::<Vec<_>>();
+	output.reverse();
+
+	output
+}
+
+/// Computes the part of the convolution selected by the mode, directly when
+/// one of the operands is short and through the FFT otherwise.
+fn compute<C: Complex>(input: Stride<C>, kernel: &[num::Complex<Precision>], mode: Mode) -> Vec<num::Complex<Precision>> {
+	let length = input.len();
+	let range  = mode.range(length, kernel.len());
+
+	if range.is_empty() {
+		return Vec::new();
+	}
+
+	if length.min(kernel.len()) <= DIRECT {
+		return range.map(|k| {
+			let start = (k + 1).saturating_sub(kernel.len());
+			let end   = k.min(length - 1);
+
+			(start .. end + 1).fold(num::Complex::zero(), |sum, j| sum + input[j].to_num() * kernel[k - j])
+		}).collect();
+	}
+
+	let size = (length + kernel.len() - 1).next_power_of_two();
+
+	let mut a = vec![num::Complex::<Precision>::zero(); size];
+	let mut b = vec![num::Complex::<Precision>::zero(); size];
+	let mut c = vec![num::Complex::<Precision>::zero(); size];
+
+	for (a, value) in a.iter_mut().zip(input.iter()) {
+		*a = value.to_num();
+	}
+
+	b[.. kernel.len()].copy_from_slice(kernel);
+
+	forward_in(&*a, &mut *c);
+	forward_in(&*b, &mut *a);
+
+	for (c, a) in c.iter_mut().zip(a.iter()) {
+		*c *= a;
+	}
+
+	inverse_in(&*c, &mut *a);
+	a[range].to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+	use num::Complex;
+	use super::Mode;
+	use Precision;
+
+	// small pseudo-random integers, so the direct sums are exact
+	fn integers(length: usize, seed: usize) -> Vec<Precision> {
+		(0 .. length).map(|n| ((n * 7919 + seed) % 23) as Precision - 11.0).collect()
+	}
+
+	#[test]
+	fn modes() {
+		let input  = [1.0, 2.0, 3.0];
+		let kernel = [0.0, 1.0, 0.5];
+
+		assert_eq!(super::convolve_real::<_, _, Precision, _, _>(&input[..], &kernel[..], Mode::Full),
+			vec![0.0, 1.0, 2.5, 4.0, 1.5]);
+
+		assert_eq!(super::convolve_real::<_, _, Precision, _, _>(&input[..], &kernel[..], Mode::Same),
+			vec![1.0, 2.5, 4.0]);
+
+		assert_eq!(super::convolve_real::<_, _, Precision, _, _>(&input[..], &kernel[..], Mode::Valid),
+			vec![2.5]);
+
+		assert_eq!(super::correlate_real::<_, _, Precision, _, _>(&input[..], &kernel[..], Mode::Full),
+			vec![0.5, 2.0, 3.5, 3.0, 0.0]);
+	}
+
+	#[test]
+	fn fft() {
+		let input  = integers(300, 1);
+		let kernel = integers(70, 2);
+
+		let mut full = vec![0.0; 369];
+
+		for (i, x) in input.iter().enumerate() {
+			for (j, h) in kernel.iter().enumerate() {
+				full[i + j] += x * h;
+			}
+		}
+
+		for &(mode, start) in &[(Mode::Full, 0), (Mode::Same, 34), (Mode::Valid, 69)] {
+			let output = super::convolve_real::<_, _, Precision, _, _>(&*input, &*kernel, mode);
+
+			assert_eq!(output.len(), mode.length(300, 70));
+
+			for (a, b) in output.iter().zip(full[start ..].iter()) {
+				assert_eq!(a.round(), *b);
+			}
+		}
+	}
+
+	#[test]
+	fn complex() {
+		let input  = integers(40, 3).into_iter().zip(integers(40, 4))
+			.map(|(re, im)| Complex::new(re, im)).collect::<Vec<_>>();
+		let kernel = vec![Complex::new(0.0, 1.0), Complex::new(0.5, -0.5)];
+
+		let output = super::correlate::<_, _, Complex<Precision>, _, _>(&*input, &*kernel, Mode::Valid);
+
+		for (k, value) in output.iter().enumerate() {
+			let expected = input[k] * kernel[0].conj() + input[k + 1] * kernel[1].conj();
+
+			assert!((value - expected).norm() < 1e-4);
+		}
+	}
+}
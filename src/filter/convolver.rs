@@ -0,0 +1,379 @@
+use std::collections::VecDeque;
+use std::sync::Arc;
+use num::{self, Zero};
+use strided::{Strided, MutStrided};
+
+use {Precision, Sample, SampleMut, forward_in, inverse_in};
+
+/// The block convolution method.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum Method {
+	/// Every block is zero-padded and the tail of its convolution is added to
+	/// the following block.
+	OverlapAdd,
+
+	/// Every block is transformed together with the previous one and the part
+	/// affected by the circular wrap-around is discarded.
+	OverlapSave,
+}
+
+/// How the impulse response is split into partitions.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum Partitioning {
+	/// All partitions have the size of a block, the cost per block grows
+	/// linearly with the length of the response.
+	Uniform,
+
+	/// Two partitions of the size of a block, then two for every doubling of
+	/// the size, so the cost per block grows logarithmically with the length of
+	/// the response.
+	///
+	/// Every partition starts at least its own size into the response, so its
+	/// contribution is always ready in time and the latency stays one block.
+	NonUniform,
+}
+
+/// The partitions of the same size.
+#[derive(Clone, Debug)]
+struct Level {
+	size:    usize,
+	offset:  usize,
+	spectra: Vec<Vec<num::Complex<Precision>>>,
+}
+
+/// The transformed partitions of an impulse response, computed once and
+/// shared by any number of convolvers.
+#[derive(Clone, Debug)]
+pub struct Plan {
+	block:  usize,
+	length: usize,
+	method: Method,
+	levels: Vec<Level>,
+}
+
+impl Plan {
+	/// Partitions and transforms the given impulse response for the given
+	/// block size, which must be a power of two.
+	pub fn new<S, I>(response: I, block: usize, method: Method, partitioning: Partitioning) -> Self
+		where S: Sample,
+		      I: Strided<Elem=S>
+	{
+		debug_assert!(block.is_power_of_two());
+
+		let     response = response.as_stride().iter().map(|s| s.normalize()).collect::<Vec<_>>();
+		let mut levels   = Vec::new();
+		let mut offset   = 0;
+		let mut size     = block;
+
+		while offset < response.len() {
+			let remaining = (response.len() - offset).div_ceil(size);
+			let count     = match partitioning {
+				Partitioning::Uniform    => remaining,
+				Partitioning::NonUniform => remaining.min(2),
+			};
+
+			let spectra = (0 .. count).map(|index| {
+				let     start  = offset + index * size;
+				let     end    = (start + size).min(response.len());
+				let mut buffer = vec![num::Complex::<Precision>::zero(); size * 2];
+				let mut output = vec![num::Complex::<Precision>::zero(); size * 2];
+
+				for (buffer, &value) in buffer.iter_mut().zip(response[start .. end].iter()) {
+					*buffer = num::Complex::new(value, 0.0);
+				}
+
+				forward_in(&*buffer, &mut *output);
+				output
+			}).collect();
+
+			levels.push(Level { size, offset, spectra });
+
+			offset += count * size;
+
+			if partitioning == Partitioning::NonUniform {
+				size *= 2;
+			}
+		}
+
+		Plan {
+			block,
+			length: response.len(),
+			method,
+			levels,
+		}
+	}
+
+	/// The block size, which is also the latency in samples.
+	#[inline(always)]
+	pub fn block(&self) -> usize {
+		self.block
+	}
+
+	/// The length of the impulse response.
+	#[inline(always)]
+	pub fn len(&self) -> usize {
+		self.length
+	}
+
+	/// Checks if the impulse response is empty.
+	#[inline(always)]
+	pub fn is_empty(&self) -> bool {
+		self.length == 0
+	}
+
+	/// The block convolution method.
+	#[inline(always)]
+	pub fn method(&self) -> Method {
+		self.method
+	}
+
+	/// The offset and size of every partition.
+	pub fn partitions(&self) -> Vec<(usize, usize)> {
+		self.levels.iter().flat_map(|level|
+			(0 .. level.spectra.len()).map(move |index| (level.offset + index * level.size, level.size))
+		).collect()
+	}
+}
+
+/// The running state of the partitions of the same size.
+#[derive(Clone, Debug)]
+struct State {
+	input:  Vec<Precision>,
+	filled: usize,
+	delay:  VecDeque<Vec<num::Complex<Precision>>>,
+	tail:   Vec<Precision>,
+}
+
+/// Streaming partitioned convolver, the input can be processed in blocks of
+/// any length and the output is delayed by exactly one block.
+#[derive(Clone, Debug)]
+pub struct Convolver {
+	plan:     Arc<Plan>,
+	states:   Vec<State>,
+	ready:    Vec<Precision>,
+	position: usize,
+	future:   VecDeque<Precision>,
+}
+
+impl Convolver {
+	/// Creates a convolver for the given plan.
+	pub fn new(plan: Arc<Plan>) -> Self {
+		let states = plan.levels.iter().map(|level| State {
+			input:  vec![0.0; level.size * 2],
+			filled: 0,
+			delay:  VecDeque::with_capacity(level.spectra.len()),
+			tail:   vec![0.0; level.size],
+		}).collect();
+
+		let horizon = plan.levels.iter()
+			.map(|level| level.offset + plan.block)
+			.max().unwrap_or(0) + plan.block;
+
+		Convolver {
+			states,
+			ready:    vec![0.0; plan.block],
+			position: 0,
+			future:   vec![0.0; horizon].into_iter().collect(),
+			plan,
+		}
+	}
+
+	/// The plan the convolver uses.
+	#[inline(always)]
+	pub fn plan(&self) -> &Arc<Plan> {
+		&self.plan
+	}
+
+	/// The delay of the output in samples.
+	#[inline(always)]
+	pub fn latency(&self) -> usize {
+		self.plan.block
+	}
+
+	/// Convolves the given input and puts the result into the given output of
+	/// the same length.
+	pub fn process<SI, SO, I, O>(&mut self, input: I, mut output: O)
+		where SI: Sample,
+		      SO: SampleMut,
+		      I:  Strided<Elem=SI>,
+		      O:  MutStrided<Elem=SO>
+	{
+		let     input  = input.as_stride();
+		let mut output = output.as_stride_mut();
+
+		debug_assert_eq!(input.len(), output.len());
+
+		let block = self.plan.block;
+
+		for (input, output) in input.iter().zip(output.iter_mut()) {
+			output.set_normalized(self.ready[self.position]);
+
+			let sample = input.normalize();
+
+			for (state, level) in self.states.iter_mut().zip(self.plan.levels.iter()) {
+				let start = match self.plan.method {
+					Method::OverlapSave => level.size,
+					Method::OverlapAdd  => 0,
+				};
+
+				state.input[start + state.filled + self.position] = sample;
+			}
+
+			self.position += 1;
+
+			if self.position == block {
+				self.position = 0;
+				self.block();
+			}
+		}
+	}
+
+	/// Clears the state, the plan is kept.
+	pub fn reset(&mut self) {
+		for state in self.states.iter_mut() {
+			state.filled = 0;
+			state.delay.clear();
+
+			for value in state.input.iter_mut().chain(state.tail.iter_mut()) {
+				*value = 0.0;
+			}
+		}
+
+		for value in self.ready.iter_mut().chain(self.future.iter_mut()) {
+			*value = 0.0;
+		}
+
+		self.position = 0;
+	}
+
+	/// Processes a complete block, running the partitions whose input is
+	/// complete and moving the output forward.
+	fn block(&mut self) {
+		let plan  = self.plan.clone();
+		let block = plan.block;
+
+		for (state, level) in self.states.iter_mut().zip(plan.levels.iter()) {
+			state.filled += block;
+
+			if state.filled < level.size {
+				continue;
+			}
+
+			state.filled = 0;
+
+			let     size     = level.size;
+			let mut buffer   = state.input.iter().map(|&v| num::Complex::new(v, 0.0)).collect::<Vec<_>>();
+			let mut spectrum = vec![num::Complex::<Precision>::zero(); size * 2];
+
+			forward_in(&*buffer, &mut *spectrum);
+
+			// frequency-domain delay line
+			if state.delay.len() == level.spectra.len() {
+				state.delay.pop_back();
+			}
+
+			state.delay.push_front(spectrum);
+
+			let mut sum = vec![num::Complex::<Precision>::zero(); size * 2];
+
+			for (input, response) in state.delay.iter().zip(level.spectra.iter()) {
+				for ((sum, input), response) in sum.iter_mut().zip(input.iter()).zip(response.iter()) {
+					*sum += input * response;
+				}
+			}
+
+			inverse_in(&*sum, &mut *buffer);
+
+			// where the output of this block lands relative to the current block
+			let position = level.offset + block - size;
+
+			match plan.method {
+				Method::OverlapSave => {
+					for (i, value) in buffer[size ..].iter().enumerate() {
+						self.future[position + i] += value.re;
+					}
+
+					let (previous, current) = state.input.split_at_mut(size);
+					previous.copy_from_slice(current);
+				}
+
+				Method::OverlapAdd => {
+					for (i, value) in buffer[.. size].iter().enumerate() {
+						self.future[position + i] += value.re + state.tail[i];
+					}
+
+					for (tail, value) in state.tail.iter_mut().zip(buffer[size ..].iter()) {
+						*tail = value.re;
+					}
+
+					for value in state.input.iter_mut() {
+						*value = 0.0;
+					}
+				}
+			}
+		}
+
+		for (ready, value) in self.ready.iter_mut().zip(self.future.drain(.. block)) {
+			*ready = value;
+		}
+
+		self.future.extend(vec![0.0; block]);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::sync::Arc;
+	use super::{Plan, Convolver, Method, Partitioning};
+	use {Precision, Mode, convolve_real};
+
+	fn signal(length: usize, step: Precision) -> Vec<Precision> {
+		(0 .. length).map(|n| (n as Precision * step).sin() * (-(n as Precision) / length as Precision).exp()).collect()
+	}
+
+	#[test]
+	fn partitions() {
+		let response = vec![0.0 as Precision; 1000];
+
+		let uniform = Plan::new(&*response, 64, Method::OverlapSave, Partitioning::Uniform);
+		assert_eq!(uniform.partitions().len(), 16);
+
+		let nonuniform = Plan::new(&*response, 64, Method::OverlapSave, Partitioning::NonUniform);
+		assert_eq!(nonuniform.partitions(), vec![(0, 64), (64, 64), (128, 128), (256, 128), (384, 256), (640, 256), (896, 512)]);
+	}
+
+	#[test]
+	fn process() {
+		let response = signal(1000, 0.7);
+		let input    = signal(3000, 0.13);
+		let expected = convolve_real::<_, _, Precision, _, _>(&*input, &*response, Mode::Full);
+
+		for &method in &[Method::OverlapAdd, Method::OverlapSave] {
+			for &partitioning in &[Partitioning::Uniform, Partitioning::NonUniform] {
+				let     plan      = Arc::new(Plan::new(&*response, 32, method, partitioning));
+				let mut convolver = Convolver::new(plan);
+				let mut output    = vec![0.0 as Precision; input.len()];
+
+				// blocks of arbitrary length
+				let mut start = 0;
+
+				for &length in [1, 7, 100, 33, 512].iter().cycle() {
+					let end = (start + length).min(input.len());
+					convolver.process(&input[start .. end], &mut output[start .. end]);
+
+					start = end;
+
+					if start == input.len() {
+						break;
+					}
+				}
+
+				assert!(output[.. 32].iter().all(|&v| v == 0.0));
+
+				for (a, b) in output[32 ..].iter().zip(expected.iter()) {
+					assert!((a - b).abs() < 1e-3, "{:?} {:?} {} {}", method, partitioning, a, b);
+				}
+			}
+		}
+	}
+}
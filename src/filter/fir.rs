@@ -0,0 +1,284 @@
+use std::f64::consts::PI;
+use num::{self, Zero};
+use strided::{Strided, MutStrided};
+
+use {Precision, Sample, SampleMut, inverse};
+use window::{self, Function, Symmetry, Kaiser};
+
+/// The ideal response of a windowed-sinc filter, the cutoffs are in hertz.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum Response {
+	/// Passes the frequencies below the cutoff.
+	LowPass(Precision),
+
+	/// Passes the frequencies above the cutoff.
+	HighPass(Precision),
+
+	/// Passes the frequencies between the two cutoffs.
+	BandPass(Precision, Precision),
+
+	/// Stops the frequencies between the two cutoffs.
+	BandStop(Precision, Precision),
+}
+
+impl Response {
+	/// The frequency in cycles per sample at which the taps are normalized to
+	/// unit gain.
+	fn reference(&self, rate: Precision) -> Precision {
+		match *self {
+			Response::LowPass(_) | Response::BandStop(..) =>
+				0.0,
+
+			Response::HighPass(_) =>
+				0.5,
+
+			Response::BandPass(low, high) =>
+				(low + high) / 2.0 / rate,
+		}
+	}
+
+	/// Checks if the response passes Nyquist, which needs an odd number of
+	/// taps since even symmetric filters always have a zero there.
+	fn passes_nyquist(&self) -> bool {
+		matches!(*self, Response::HighPass(_) | Response::BandStop(..))
+	}
+}
+
+/// Designs a filter with the given number of taps using the given window
+/// function.
+#[inline(always)]
+pub fn design<F: Function + 'static>(response: Response, taps: usize, rate: u32) -> Vec<Precision> {
+	let window = window::generate::<F, Precision, _>(.., Symmetry::Symmetric, taps);
+	design_with(response, rate, &**window)
+}
+
+/// Designs a filter using the given window coefficients, the number of taps is
+/// the length of the window.
+///
+/// Parametric windows like `Kaiser` and `Chebyshev` go through here.
+#[inline(always)]
+pub fn design_with<S, W>(response: Response, rate: u32, window: W) -> Vec<Precision>
+	where S: Sample,
+	      W: Strided<Elem=S>
+{
+	let mut output = vec![0.0; window.as_stride().len()];
+	design_in(response, rate, window, &mut *output);
+
+	output
+}
+
+/// Designs a filter using the given window coefficients and puts the taps into
+/// the given output of the same length.
+pub fn design_in<SW, SO, W, O>(response: Response, rate: u32, window: W, mut output: O)
+	where SW: Sample,
+	      SO: SampleMut,
+	      W:  Strided<Elem=SW>,
+	      O:  MutStrided<Elem=SO>
+{
+	let     window = window.as_stride();
+	let mut output = output.as_stride_mut();
+	let     taps   = window.len();
+	let     rate   = rate as Precision;
+
+	debug_assert_eq!(taps, output.len());
+	debug_assert!(taps % 2 == 1 || !response.passes_nyquist());
+
+	if taps == 0 {
+		return;
+	}
+
+	let center = (taps - 1) as f64 / 2.0;
+	let ideal  = |n: usize| {
+		let t = n as f64 - center;
+
+		// the sinc at the given cutoff in cycles per sample
+		let lowpass = |cutoff: Precision| {
+			let cutoff = cutoff as f64 / rate as f64;
+
+			if t == 0.0 {
+				2.0 * cutoff
+			}
+			else {
+				(2.0 * PI * cutoff * t).sin() / (PI * t)
+			}
+		};
+
+		let delta = if t == 0.0 { 1.0 } else { 0.0 };
+
+		(match response {
+			Response::LowPass(cutoff)     => lowpass(cutoff),
+			Response::HighPass(cutoff)    => delta - lowpass(cutoff),
+			Response::BandPass(low, high) => lowpass(high) - lowpass(low),
+			Response::BandStop(low, high) => delta - lowpass(high) + lowpass(low),
+		}) as Precision
+	};
+
+	let taps = (0 .. taps).zip(window.iter())
+		.map(|(n, w)| ideal(n) * w.normalize())
+		.collect::<Vec<_>>();
+
+	// unit gain at the center of the passband
+	let reference = response.reference(rate) as f64;
+	let gain      = taps.iter().enumerate().fold(num::Complex::<f64>::zero(), |sum, (n, &h)|
+		sum + num::Complex::from_polar(&(h as f64), &(-2.0 * PI * reference * n as f64))).norm();
+
+	for (output, &h) in output.iter_mut().zip(taps.iter()) {
+		output.set_normalized(h / gain as Precision);
+	}
+}
+
+/// Estimates the number of taps and the Kaiser window needed for the given
+/// stopband attenuation in decibels and transition width in hertz.
+///
+/// The estimates are the empirical formulas by Kaiser, the number of taps is
+/// rounded up to an odd number so any response can use it.
+pub fn kaiser(attenuation: Precision, transition: Precision, rate: u32) -> (usize, Kaiser) {
+	debug_assert!(transition > 0.0);
+
+	let a    = attenuation.abs() as f64;
+	let beta = if a > 50.0 {
+		0.1102 * (a - 8.7)
+	}
+	else if a >= 21.0 {
+		0.5842 * (a - 21.0).powf(0.4) + 0.07886 * (a - 21.0)
+	}
+	else {
+		0.0
+	};
+
+	let width = 2.0 * PI * transition as f64 / rate as f64;
+	let taps  = ((a - 7.95) / (2.285 * width) + 1.0).ceil().max(1.0) as usize;
+
+	(taps | 1, Kaiser::new(beta as Precision))
+}
+
+/// Designs a filter with the given number of taps from the given magnitude
+/// response using the given window function.
+///
+/// The gains are equally spaced from DC to Nyquist, both included, and are
+/// linearly interpolated on a dense grid, transformed back to the time domain
+/// with a linear phase and windowed. With an even number of taps the gain at
+/// Nyquist should be zero.
+#[inline(always)]
+pub fn sampling<F: Function + 'static>(gains: &[Precision], taps: usize) -> Vec<Precision> {
+	let window = window::generate::<F, Precision, _>(.., Symmetry::Symmetric, taps);
+	sampling_with(gains, &**window)
+}
+
+/// Designs a filter from the given magnitude response using the given window
+/// coefficients, the number of taps is the length of the window.
+pub fn sampling_with<S, W>(gains: &[Precision], window: W) -> Vec<Precision>
+	where S: Sample,
+	      W: Strided<Elem=S>
+{
+	let window = window.as_stride();
+	let taps   = window.len();
+
+	debug_assert!(gains.len() >= 2);
+
+	if taps == 0 {
+		return Vec::new();
+	}
+
+	let size = ((gains.len() - 1) * 2).max(taps * 2).next_power_of_two();
+	let half = size / 2;
+	let last = (gains.len() - 1) as f64;
+
+	let mut spectrum = vec![num::Complex::<Precision>::zero(); size];
+
+	for k in 0 .. half + 1 {
+		let position = k as f64 / half as f64 * last;
+		let index    = (position as usize).min(gains.len() - 2);
+		let fraction = position - index as f64;
+		let gain     = gains[index] as f64 * (1.0 - fraction) + gains[index + 1] as f64 * fraction;
+
+		// delay by half the filter length for a linear phase
+		let value = num::Complex::from_polar(&gain, &(-PI * k as f64 * (taps - 1) as f64 / size as f64));
+
+		spectrum[k]                 = num::Complex::new(value.re as Precision, value.im as Precision);
+		spectrum[(size - k) % size] = spectrum[k].conj();
+	}
+
+	// the Nyquist bin of a real signal is real
+	spectrum[half].im = 0.0;
+
+	let impulse = inverse::<_, num::Complex<Precision>, _>(&*spectrum);
+
+	impulse.iter().zip(window.iter())
+		.map(|(h, w)| h.re * w.normalize())
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::Response;
+	use window::{Hamming, Rectangular};
+	use transform::goertzel::power;
+	use Precision;
+
+	fn gain(taps: &[Precision], frequency: Precision) -> Precision {
+		power(taps, frequency, 8000).sqrt()
+	}
+
+	#[test]
+	fn responses() {
+		let lowpass = super::design::<Hamming>(Response::LowPass(1000.0), 101, 8000);
+		assert!((gain(&lowpass, 0.0) - 1.0).abs() < 1e-3);
+		assert!(gain(&lowpass, 500.0) > 0.99);
+		assert!(gain(&lowpass, 1500.0) < 0.01);
+
+		let highpass = super::design::<Hamming>(Response::HighPass(1000.0), 101, 8000);
+		assert!((gain(&highpass, 4000.0) - 1.0).abs() < 1e-3);
+		assert!(gain(&highpass, 500.0) < 0.01);
+		assert!(gain(&highpass, 2000.0) > 0.99);
+
+		let bandpass = super::design::<Hamming>(Response::BandPass(1000.0, 2000.0), 101, 8000);
+		assert!((gain(&bandpass, 1500.0) - 1.0).abs() < 1e-3);
+		assert!(gain(&bandpass, 300.0) < 0.01);
+		assert!(gain(&bandpass, 3000.0) < 0.01);
+
+		let bandstop = super::design::<Hamming>(Response::BandStop(1000.0, 2000.0), 101, 8000);
+		assert!((gain(&bandstop, 0.0) - 1.0).abs() < 1e-3);
+		assert!(gain(&bandstop, 1500.0) < 0.01);
+		assert!(gain(&bandstop, 3000.0) > 0.99);
+
+		// linear phase
+		for (a, b) in lowpass.iter().zip(lowpass.iter().rev()) {
+			assert!((a - b).abs() < 1e-6);
+		}
+	}
+
+	#[test]
+	fn kaiser() {
+		let (taps, window) = super::kaiser(60.0, 400.0, 8000);
+
+		assert_eq!(taps, 75);
+		assert!((window.beta() - 5.653).abs() < 1e-3);
+
+		let lowpass = super::design_with(Response::LowPass(1000.0), 8000, &*window.compute(taps));
+		assert!(gain(&lowpass, 800.0) > 0.99);
+
+		for frequency in (1200 .. 4000).step_by(50) {
+			assert!(gain(&lowpass, frequency as Precision) < 1e-3);
+		}
+	}
+
+	#[test]
+	fn sampling() {
+		let taps = super::sampling::<Hamming>(&[1.0, 1.0, 0.0, 0.0, 0.0], 65);
+
+		assert!((gain(&taps, 0.0) - 1.0).abs() < 1e-2);
+		assert!(gain(&taps, 2500.0) < 0.02);
+
+		for (a, b) in taps.iter().zip(taps.iter().rev()) {
+			assert!((a - b).abs() < 1e-6);
+		}
+
+		// a flat response is a delayed impulse
+		let taps = super::sampling::<Rectangular>(&[1.0, 1.0], 9);
+
+		for (n, &h) in taps.iter().enumerate() {
+			assert!((h - if n == 4 { 1.0 } else { 0.0 }).abs() < 1e-4);
+		}
+	}
+}
@@ -0,0 +1,219 @@
+use std::f64::consts::PI;
+use num::{self, Zero, Float};
+use strided::Strided;
+
+use {Precision, Sample, forward};
+use spectrum::{self, bandwidth};
+
+/// The frequency response of a filter at a set of frequencies.
+#[derive(Clone, Debug)]
+pub struct Response {
+	frequencies: Vec<Precision>,
+	values:      Vec<num::Complex<Precision>>,
+	delays:      Vec<Precision>,
+}
+
+impl Response {
+	/// The frequencies in hertz.
+	#[inline(always)]
+	pub fn frequencies(&self) -> &[Precision] {
+		&self.frequencies
+	}
+
+	/// The complex response at every frequency.
+	#[inline(always)]
+	pub fn values(&self) -> &[num::Complex<Precision>] {
+		&self.values
+	}
+
+	/// The magnitude in decibels at every frequency.
+	pub fn magnitude(&self) -> Vec<Precision> {
+		self.values.iter().map(|c| 20.0 * c.norm().max(Precision::MIN_POSITIVE).log10()).collect()
+	}
+
+	/// The unwrapped phase in radians at every frequency.
+	#[inline(always)]
+	pub fn phase(&self) -> Vec<Precision> {
+		spectrum::unwrap(&*self.values)
+	}
+
+	/// The group delay in samples at every frequency.
+	///
+	/// The delay is undefined where the response is zero, it's zero there.
+	#[inline(always)]
+	pub fn delay(&self) -> &[Precision] {
+		&self.delays
+	}
+}
+
+/// Computes the response of the filter with the given numerator and
+/// denominator coefficients at the given number of points equally spaced from
+/// DC up to, but excluding, Nyquist.
+///
+/// The values come from zero-padded FFTs of the coefficients, an FIR filter
+/// has a denominator of `[1.0]`.
+pub fn freqz<SB, SA, B, A>(b: B, a: A, points: usize, rate: u32) -> Response
+	where SB: Sample,
+	      SA: Sample,
+	      B:  Strided<Elem=SB>,
+	      A:  Strided<Elem=SA>
+{
+	let b = coefficients(b);
+	let a = coefficients(a);
+
+	debug_assert!(!a.is_empty());
+
+	let size = points * 2;
+
+	let numerator   = dft(&b, size, false);
+	let denominator = dft(&a, size, false);
+	let ramped      = (dft(&b, size, true), dft(&a, size, true));
+
+	let values = (0 .. points).map(|k| numerator[k] / denominator[k]).collect();
+	let delays = (0 .. points).map(|k|
+		delay(numerator[k], ramped.0[k]) - delay(denominator[k], ramped.1[k])).collect();
+
+	Response {
+		frequencies: (0 .. points).map(|k| k as Precision * bandwidth(size, rate)).collect(),
+		values,
+		delays,
+	}
+}
+
+/// Computes the response of the filter with the given numerator and
+/// denominator coefficients at the given frequencies in hertz.
+pub fn freqz_at<SB, SA, B, A>(b: B, a: A, frequencies: &[Precision], rate: u32) -> Response
+	where SB: Sample,
+	      SA: Sample,
+	      B:  Strided<Elem=SB>,
+	      A:  Strided<Elem=SA>
+{
+	let b = coefficients(b);
+	let a = coefficients(a);
+
+	debug_assert!(!a.is_empty());
+
+	let mut values = Vec::with_capacity(frequencies.len());
+	let mut delays = Vec::with_capacity(frequencies.len());
+
+	for &frequency in frequencies {
+		let omega = 2.0 * PI * frequency as f64 / rate as f64;
+
+		let (numerator, ramped) = evaluate(&b, omega);
+		let (denominator, ramp) = evaluate(&a, omega);
+		let value               = numerator / denominator;
+
+		values.push(num::Complex::new(value.re as Precision, value.im as Precision));
+		delays.push((delay(numerator, ramped) - delay(denominator, ramp)) as Precision);
+	}
+
+	Response {
+		frequencies: frequencies.to_vec(),
+		values,
+		delays,
+	}
+}
+
+fn coefficients<S, I>(input: I) -> Vec<Precision>
+	where S: Sample,
+	      I: Strided<Elem=S>
+{
+	input.as_stride().iter().map(|s| s.normalize()).collect()
+}
+
+/// The DFT of the given coefficients, optionally multiplied by their index,
+/// on the given number of points.
+///
+/// Coefficients past the size wrap around, which samples the same DTFT.
+fn dft(coefficients: &[Precision], size: usize, ramp: bool) -> Vec<num::Complex<Precision>> {
+	let mut buffer = vec![num::Complex::<Precision>::zero(); size];
+
+	for (n, &value) in coefficients.iter().enumerate() {
+		let value = if ramp { value * n as Precision } else { value };
+		buffer[n % size].re += value;
+	}
+
+	forward::<_, num::Complex<Precision>, _>(&*buffer)
+}
+
+/// Evaluates the polynomial in `e^-iω` and its index-weighted version.
+fn evaluate(coefficients: &[Precision], omega: f64) -> (num::Complex<f64>, num::Complex<f64>) {
+	coefficients.iter().enumerate().fold((num::Complex::zero(), num::Complex::zero()), |(sum, ramp), (n, &value)| {
+		let term = num::Complex::from_polar(&(value as f64), &(-omega * n as f64));
+
+		(sum + term, ramp + term * n as f64)
+	})
+}
+
+/// The group delay of a polynomial from its value and the value of its
+/// index-weighted version.
+#[inline]
+fn delay<T: Float>(value: num::Complex<T>, ramped: num::Complex<T>) -> T {
+	if value.norm_sqr() <= T::min_positive_value() {
+		return T::zero();
+	}
+
+	(ramped / value).re
+}
+
+#[cfg(test)]
+mod tests {
+	use std::f64::consts::PI;
+	use super::{freqz, freqz_at};
+	use filter::fir::{self, Response};
+	use window::Hamming;
+	use Precision;
+
+	#[test]
+	fn fir() {
+		let taps     = fir::design::<Hamming>(Response::LowPass(1000.0), 31, 8000);
+		let response = freqz(&*taps, &[1.0][..], 64, 8000);
+
+		assert_eq!(response.frequencies()[16], 1000.0);
+		assert!(response.magnitude()[0].abs() < 1e-3);
+		assert!(response.magnitude()[40] < -40.0);
+
+		// linear phase, constant delay of half the length
+		for &delay in &response.delay()[.. 20] {
+			assert!((delay - 15.0).abs() < 1e-2);
+		}
+
+		let phase = response.phase();
+
+		for (k, &phase) in phase[.. 20].iter().enumerate() {
+			let expected = -15.0 * PI as Precision * k as Precision / 64.0;
+			assert!((phase - expected).abs() < 1e-2);
+		}
+
+		// the same values at arbitrary frequencies
+		let at = freqz_at(&*taps, &[1.0][..], response.frequencies(), 8000);
+
+		for (a, b) in at.values().iter().zip(response.values().iter()) {
+			assert!((a - b).norm() < 1e-4);
+		}
+	}
+
+	#[test]
+	fn iir() {
+		// one pole lowpass y[n] = x[n] + 0.5 y[n - 1]
+		let b = [1.0];
+		let a = [1.0, -0.5];
+
+		let response = freqz_at(&b[..], &a[..], &[0.0, 2000.0, 4000.0], 8000);
+
+		assert!((response.values()[0].norm() - 2.0).abs() < 1e-5);
+		assert!((response.values()[1].norm() - 1.0 / (1.25 as Precision).sqrt()).abs() < 1e-5);
+		assert!((response.values()[2].norm() - 2.0 / 3.0).abs() < 1e-5);
+
+		// τ(ω) = (0.5 cos ω - 0.25) / (1.25 - cos ω)
+		assert!((response.delay()[0] - 1.0).abs() < 1e-5);
+		assert!((response.delay()[1] + 0.2).abs() < 1e-5);
+		assert!((response.delay()[2] + 1.0 / 3.0).abs() < 1e-5);
+
+		let sampled = freqz(&b[..], &a[..], 4, 8000);
+
+		for (a, b) in sampled.delay().iter().step_by(2).zip(response.delay().iter()) {
+			assert!((a - b).abs() < 1e-4);
+		}
+	}
+}
@@ -0,0 +1,14 @@
+/// Streaming partitioned convolution.
+pub mod convolver;
+pub use self::convolver::{Convolver, Plan, Method, Partitioning};
+
+/// FIR filter design.
+pub mod fir;
+
+/// Frequency response evaluation.
+pub mod freqz;
+pub use self::freqz::{freqz, freqz_at};
+
+/// Sample rate conversion.
+pub mod resample;
+pub use self::resample::{Resampler, resample, resample_in};
@@ -0,0 +1,343 @@
+use std::collections::VecDeque;
+use num::{self, Zero};
+use strided::{Strided, MutStrided};
+
+use {Precision, Sample, SampleMut, forward, inverse};
+use super::fir::{self, Response};
+
+/// The default transition width of the polyphase filter, relative to the
+/// lower Nyquist frequency.
+pub const TRANSITION: Precision = 0.2;
+
+/// The stopband attenuation in decibels of the polyphase filter.
+const ATTENUATION: Precision = 80.0;
+
+/// Resamples the given input to the given length and returns a vector of
+/// samples.
+///
+/// The spectrum is truncated or zero-padded, so the input is assumed to be
+/// periodic, like `scipy.signal.resample`.
+#[inline(always)]
+pub fn resample<SI, SO, I>(input: I, length: usize) -> Vec<SO>
+	where SI: Sample,
+	      SO: SampleMut,
+	      I:  Strided<Elem=SI>
+{
+	let mut output = vec![SO::zero(); length];
+	resample_in(input, &mut *output);
+
+	output
+}
+
+/// Resamples the given input to the length of the given output.
+pub fn resample_in<SI, SO, I, O>(input: I, mut output: O)
+	where SI: Sample,
+	      SO: SampleMut,
+	      I:  Strided<Elem=SI>,
+	      O:  MutStrided<Elem=SO>
+{
+	let     input  = input.as_stride();
+	let mut output = output.as_stride_mut();
+
+	let from = input.len();
+	let to   = output.len();
+
+	if from == 0 || to == 0 {
+		return;
+	}
+
+	let spectrum = forward::<_, num::Complex<Precision>, _>(input);
+	let shared   = from.min(to);
+
+	let     half    = shared.div_ceil(2);
+	let mut resized = vec![num::Complex::<Precision>::zero(); to];
+
+	resized[.. half].copy_from_slice(&spectrum[.. half]);
+
+	for k in 1 .. half {
+		resized[to - k] = spectrum[from - k];
+	}
+
+	// the Nyquist bin of the shorter length is split or folded
+	if shared % 2 == 0 {
+		let k = shared / 2;
+
+		if from < to {
+			resized[k]      = spectrum[k] / 2.0;
+			resized[to - k] = spectrum[k] / 2.0;
+		}
+		else if from > to {
+			resized[k] = spectrum[k] + spectrum[from - k];
+		}
+		else {
+			resized[k] = spectrum[k];
+		}
+	}
+
+	let scale = to as Precision / from as Precision;
+
+	for (output, value) in output.iter_mut().zip(inverse::<_, num::Complex<Precision>, _>(&*resized)) {
+		output.set_normalized(value.re * scale);
+	}
+}
+
+/// Streaming polyphase resampler between two integer rates.
+///
+/// The rates are reduced to `L / M`, the input is conceptually upsampled by
+/// `L`, lowpass filtered and downsampled by `M`, but only the filter phases
+/// that produce an output are computed.
+#[derive(Clone, Debug)]
+pub struct Resampler {
+	up:      usize,
+	down:    usize,
+	phases:  Vec<Vec<Precision>>,
+	history: VecDeque<Precision>,
+	offset:  usize,
+}
+
+impl Resampler {
+	/// Creates a resampler from the given rate to the given rate with the
+	/// default transition width.
+	#[inline(always)]
+	pub fn new(from: u32, to: u32) -> Self {
+		Resampler::with_transition(from, to, TRANSITION)
+	}
+
+	/// Creates a resampler from the given rate to the given rate with the given
+	/// transition width relative to the lower Nyquist frequency, a narrower
+	/// transition needs a longer filter.
+	pub fn with_transition(from: u32, to: u32, transition: Precision) -> Self {
+		debug_assert!(from > 0 && to > 0);
+		debug_assert!(transition > 0.0 && transition < 1.0);
+
+		let divisor = gcd(from, to);
+		let up      = (to / divisor) as usize;
+		let down    = (from / divisor) as usize;
+
+		// the filter is designed in units of the lower Nyquist frequency, where
+		// the stopband starts, the upsampled rate in hertz overflows for large
+		// coprime rates
+		let rate   = (2 * up.max(down)) as u32;
+		let cutoff = 1.0 - transition / 2.0;
+
+		let (taps, window) = fir::kaiser(ATTENUATION, transition, rate);
+		let count          = taps.div_ceil(up);
+		let length         = count * up;
+
+		let prototype = if up == 1 && down == 1 {
+			let mut identity = vec![0.0; length];
+			identity[(length - 1) / 2] = 1.0;
+			identity
+		}
+		else {
+			fir::design_with(Response::LowPass(cutoff), rate, &*window.compute(length))
+		};
+
+		let phases = (0 .. up).map(|phase|
+			(0 .. count).map(|t| prototype[phase + t * up] * up as Precision).collect()
+		).collect();
+
+		Resampler {
+			up,
+			down,
+			phases,
+			history: vec![0.0; count].into_iter().collect(),
+			offset:  0,
+		}
+	}
+
+	/// The reduced upsampling and downsampling factors.
+	#[inline(always)]
+	pub fn ratio(&self) -> (usize, usize) {
+		(self.up, self.down)
+	}
+
+	/// The delay of the output in output samples.
+	pub fn latency(&self) -> Precision {
+		let length = self.phases[0].len() * self.up;
+
+		(length - 1) as Precision / 2.0 / self.down as Precision
+	}
+
+	/// The number of samples the next call with an input of the given length
+	/// produces.
+	pub fn length(&self, input: usize) -> usize {
+		let end = input * self.up;
+
+		if end <= self.offset {
+			return 0;
+		}
+
+		(end - self.offset).div_ceil(self.down)
+	}
+
+	/// Resamples the given input and returns a vector of samples.
+	#[inline(always)]
+	pub fn process<SI, SO, I>(&mut self, input: I) -> Vec<SO>
+		where SI: Sample,
+		      SO: SampleMut,
+		      I:  Strided<Elem=SI>
+	{
+		let mut output = vec![SO::zero(); self.length(input.as_stride().len())];
+		self.process_in(input, &mut *output);
+
+		output
+	}
+
+	/// Resamples the given input into the given output, which must hold at
+	/// least `length(input)` samples, and returns the number of samples
+	/// written.
+	pub fn process_in<SI, SO, I, O>(&mut self, input: I, mut output: O) -> usize
+		where SI: Sample,
+		      SO: SampleMut,
+		      I:  Strided<Elem=SI>,
+		      O:  MutStrided<Elem=SO>
+	{
+		let     input   = input.as_stride();
+		let mut output  = output.as_stride_mut();
+		let mut written = 0;
+
+		debug_assert!(output.len() >= self.length(input.len()));
+
+		for sample in input.iter() {
+			self.history.pop_back();
+			self.history.push_front(sample.normalize());
+
+			while self.offset < self.up {
+				let value = self.phases[self.offset].iter().zip(self.history.iter())
+					.fold(0.0, |sum, (h, x)| sum + h * x);
+
+				output[written].set_normalized(value);
+				written     += 1;
+				self.offset += self.down;
+			}
+
+			self.offset -= self.up;
+		}
+
+		written
+	}
+
+	/// Clears the input history.
+	pub fn reset(&mut self) {
+		for value in self.history.iter_mut() {
+			*value = 0.0;
+		}
+
+		self.offset = 0;
+	}
+}
+
+fn gcd(mut a: u32, mut b: u32) -> u32 {
+	while b != 0 {
+		let rest = a % b;
+
+		a = b;
+		b = rest;
+	}
+
+	a
+}
+
+#[cfg(test)]
+mod tests {
+	use std::f64::consts::PI;
+	use super::{resample, Resampler};
+	use Precision;
+
+	fn sine(length: usize, frequency: Precision, rate: Precision, delay: Precision) -> Vec<Precision> {
+		(0 .. length).map(|n| (2.0 * PI as Precision * frequency * (n as Precision - delay) / rate).sin()).collect()
+	}
+
+	#[test]
+	fn fourier() {
+		// periodic in both lengths
+		let input = (0 .. 40).map(|n| (2.0 * PI as Precision * 3.0 * n as Precision / 40.0).cos()
+			+ 0.5 * (2.0 * PI as Precision * 7.0 * n as Precision / 40.0).sin()).collect::<Vec<_>>();
+
+		for &length in &[25, 40, 64, 101] {
+			let output = resample::<_, Precision, _>(&*input, length);
+
+			for (n, value) in output.iter().enumerate() {
+				let t        = n as Precision / length as Precision;
+				let expected = (2.0 * PI as Precision * 3.0 * t).cos() + 0.5 * (2.0 * PI as Precision * 7.0 * t).sin();
+
+				assert!((value - expected).abs() < 1e-3, "{} {} {}", length, value, expected);
+			}
+		}
+
+		// the Nyquist component of an even length is preserved when upsampling
+		let alternating = [1.0, -1.0, 1.0, -1.0];
+		let output      = resample::<_, Precision, _>(&alternating[..], 8);
+
+		for (n, value) in output.iter().enumerate() {
+			assert!((value - (PI as Precision * n as Precision / 2.0).cos()).abs() < 1e-5);
+		}
+	}
+
+	#[test]
+	fn polyphase() {
+		let mut resampler = Resampler::new(44100, 48000);
+		assert_eq!(resampler.ratio(), (160, 147));
+
+		let     input  = sine(4410, 1000.0, 44100.0, 0.0);
+		let mut output = Vec::new();
+
+		for chunk in input.chunks(333) {
+			let length = resampler.length(chunk.len());
+			let chunk  = resampler.process::<_, Precision, _>(chunk);
+
+			assert_eq!(chunk.len(), length);
+			output.extend_from_slice(&chunk);
+		}
+
+		assert!((output.len() as isize - 4800).abs() <= 1);
+
+		let expected = sine(output.len(), 1000.0, 48000.0, resampler.latency());
+
+		for (a, b) in output[200 ..].iter().zip(expected[200 ..].iter()) {
+			assert!((a - b).abs() < 1e-3, "{} {}", a, b);
+		}
+	}
+
+	#[test]
+	fn downsample() {
+		let mut resampler = Resampler::new(48000, 16000);
+		assert_eq!(resampler.ratio(), (1, 3));
+
+		// above the new Nyquist frequency is removed
+		let input  = sine(4800, 12000.0, 48000.0, 0.0);
+		let output = resampler.process::<_, Precision, _>(&*input);
+
+		assert_eq!(output.len(), 1600);
+		assert!(output[100 ..].iter().all(|v| v.abs() < 1e-3));
+
+		resampler.reset();
+
+		let input    = sine(4800, 2000.0, 48000.0, 0.0);
+		let output   = resampler.process::<_, Precision, _>(&*input);
+		let expected = sine(1600, 2000.0, 16000.0, resampler.latency());
+
+		for (a, b) in output[100 ..].iter().zip(expected[100 ..].iter()) {
+			assert!((a - b).abs() < 1e-3, "{} {}", a, b);
+		}
+	}
+
+	#[test]
+	fn coprime() {
+		// the upsampled rate doesn't fit in 32 bits
+		let mut resampler = Resampler::with_transition(192_000, 44_101, 0.9);
+		assert_eq!(resampler.ratio(), (44_101, 192_000));
+
+		let input  = sine(19_200, 1000.0, 192_000.0, 0.0);
+		let output = resampler.process::<_, Precision, _>(&*input);
+
+		assert!((output.len() as isize - 4410).abs() <= 1);
+
+		let expected = sine(output.len(), 1000.0, 44_101.0, resampler.latency());
+
+		for (a, b) in output[200 ..].iter().zip(expected[200 ..].iter()) {
+			assert!((a - b).abs() < 1e-3, "{} {}", a, b);
+		}
+	}
+}
@@ -33,6 +33,11 @@ pub use sample::{Sample, SampleMut};
 mod complex;
 pub use complex::{Complex, ComplexMut};
 
+mod convolution;
+pub use convolution::{Mode, DIRECT};
+pub use convolution::{convolve, convolve_in, convolve_real, convolve_real_in};
+pub use convolution::{correlate, correlate_in, correlate_real, correlate_real_in};
+
 /// Various algorithms to compute the fourier transform.
 pub mod transform;
 use transform::{cooley_tukey, bluestein};
@@ -44,6 +49,12 @@ pub use window::Window;
 /// Spectrum computations.
 pub mod spectrum;
 
+/// Feature extraction built on the transforms and spectrum computations.
+pub mod analysis;
+
+/// Filter design and application.
+pub mod filter;
+
 /// Applies a forward fourier transform to the given input and returns a vector
 /// of complex numbers.
 #[inline(always)]
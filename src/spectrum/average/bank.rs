@@ -0,0 +1,116 @@
+use strided::{Stride, MutStride};
+
+use {Precision};
+use spectrum::bandwidth;
+
+/// The shape of the bands in a filterbank.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Band {
+	/// Triangular overlapping bands, each one spans from the center of the
+	/// previous band to the center of the next.
+	Triangular,
+
+	/// Rectangular bands, each bin belongs to a single band.
+	Rectangular,
+}
+
+impl Band {
+	/// The number of edges needed for the given number of bands.
+	#[inline(always)]
+	pub fn edges(&self, bands: usize) -> usize {
+		match *self {
+			Band::Triangular  => bands + 2,
+			Band::Rectangular => bands + 1,
+		}
+	}
+}
+
+/// The normalization applied to the filters.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Normalization {
+	/// Every filter peaks at `1.0`.
+	None,
+
+	/// Every filter has unit area, so wider filters don't get more energy.
+	Area,
+}
+
+/// Computes the edges in hertz of the given number of bands equally spaced on
+/// a scale between the given frequencies.
+pub fn edges<T, F>(to: T, from: F, band: Band, bands: usize, min: u32, max: u32) -> Vec<Precision>
+	where T: Fn(Precision) -> Precision,
+	      F: Fn(Precision) -> Precision
+{
+	let count = band.edges(bands);
+	let low   = to(min as Precision);
+	let high  = to(max as Precision);
+	let step  = (high - low) / (count - 1) as Precision;
+
+	// the outer edges are pinned so they don't drift with the conversion
+	(0 .. count).map(|i| match i {
+		0                   => min as Precision,
+		i if i == count - 1 => max as Precision,
+		i                   => from(low + step * i as Precision),
+	}).collect()
+}
+
+/// Computes the weight of every bin in a spectrum with the given number of bins
+/// for the given band.
+pub fn weights(edges: &[Precision], band: Band, normalization: Normalization, index: usize, rate: u32, bins: usize) -> Vec<Precision> {
+	let bandwidth = bandwidth((bins - 1) * 2, rate);
+
+	match band {
+		Band::Triangular => {
+			let low    = edges[index];
+			let center = edges[index + 1];
+			let high   = edges[index + 2];
+
+			let scale = match normalization {
+				Normalization::None => 1.0,
+				Normalization::Area => 2.0 / (high - low),
+			};
+
+			(0 .. bins).map(|bin| {
+				let frequency = bin as Precision * bandwidth;
+				let rising    = (frequency - low) / (center - low);
+				let falling   = (high - frequency) / (high - center);
+
+				rising.min(falling).max(0.0) * scale
+			}).collect()
+		}
+
+		Band::Rectangular => {
+			let low  = edges[index];
+			let high = edges[index + 1];
+			let last = index + 2 == edges.len();
+
+			let scale = match normalization {
+				Normalization::None => 1.0,
+				Normalization::Area => 1.0 / (high - low),
+			};
+
+			(0 .. bins).map(|bin| {
+				let frequency = bin as Precision * bandwidth;
+
+				// the last band includes its upper edge
+				if frequency >= low && (frequency < high || (last && frequency <= high)) {
+					scale
+				}
+				else {
+					0.0
+				}
+			}).collect()
+		}
+	}
+}
+
+/// Applies the filterbank with the given edges on the given input.
+pub fn compute(edges: &[Precision], band: Band, normalization: Normalization, rate: u32, input: Stride<Precision>, mut output: MutStride<Precision>) {
+	for (index, output) in output.iter_mut().enumerate() {
+		let weights = weights(edges, band, normalization, index, rate, input.len());
+
+		*output = input.iter().zip(weights.iter())
+			.map(|(value, weight)| value * weight)
+			.sum();
+	}
+}
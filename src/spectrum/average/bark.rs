@@ -0,0 +1,98 @@
+use strided::{Stride, MutStride};
+
+use {Precision};
+use spectrum::average::Average;
+use spectrum::average::bank::{self, Band, Normalization};
+
+/// Bark critical-band average.
+///
+/// The arguments are the sample rate, the number of bands, the minimum and
+/// maximum frequency, the shape of the bands and their normalization.
+///
+/// The bands are equally spaced on the Bark scale using Traunmüller's formula.
+pub struct Bark;
+
+impl Bark {
+	/// Converts the given frequency in hertz to Bark.
+	#[inline]
+	pub fn bark(hz: Precision) -> Precision {
+		26.81 * hz / (1960.0 + hz) - 0.53
+	}
+
+	/// Converts the given Bark to a frequency in hertz.
+	#[inline]
+	pub fn hz(bark: Precision) -> Precision {
+		1960.0 * (bark + 0.53) / (26.28 - bark)
+	}
+
+	/// Computes the edges in hertz of the bands.
+	pub fn edges(&(_, bands, min, max, band, _): &<Self as Average>::Arguments) -> Vec<Precision> {
+		bank::edges(Bark::bark, Bark::hz, band, bands, min, max)
+	}
+}
+
+impl Average for Bark {
+	type Arguments = (u32, usize, u32, u32, Band, Normalization);
+
+	#[inline]
+	fn size(&(_, bands, _, _, _, _): &Self::Arguments) -> usize {
+		bands
+	}
+
+	fn compute(args: &Self::Arguments, input: Stride<Precision>, output: MutStride<Precision>) {
+		let &(rate, bands, min, max, band, normalization) = args;
+
+		debug_assert!(min < max && max <= rate / 2);
+		debug_assert_eq!(output.len(), bands);
+
+		bank::compute(&Bark::edges(args), band, normalization, rate, input, output);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::Bark;
+	use spectrum::average::{self, Band, Normalization};
+
+	macro_rules! assert_approx_eq {
+		($a:expr, $b:expr, $p:expr) => (
+			assert_eq!(format!("{:.1$}", $a, $p), format!("{:.1$}", $b, $p));
+		)
+	}
+
+	#[test]
+	fn scale() {
+		assert_approx_eq!(Bark::bark(1000.0), 8.53, 2);
+		assert_approx_eq!(Bark::hz(Bark::bark(4000.0)), 4000.0, 0);
+	}
+
+	#[test]
+	fn compute() {
+		// the edges are at 395, 990 and 1986 Hz, the bins are 15.625 Hz wide
+		let args   = (8000, 4, 0, 4000, Band::Rectangular, Normalization::None);
+		let input  = vec![1.0; 257];
+		let output = average::compute::<Bark, _>(&args, &*input);
+
+		assert_eq!(output, vec![26.0, 38.0, 64.0, 129.0]);
+
+		// with unit area a flat spectrum gets the bin density of every band
+		let args   = (8000, 4, 0, 4000, Band::Rectangular, Normalization::Area);
+		let edges  = Bark::edges(&args);
+		let output = average::compute::<Bark, _>(&args, &*input);
+
+		for (i, (output, &count)) in output.iter().zip([26.0, 38.0, 64.0, 129.0].iter()).enumerate() {
+			assert_approx_eq!(output * (edges[i + 1] - edges[i]), count, 3);
+		}
+
+		// a single bin only lights up the triangular bands around it
+		let     args  = (8000, 4, 0, 4000, Band::Triangular, Normalization::None);
+		let mut input = vec![0.0; 257];
+
+		input[3] = 1.0;
+
+		let output = average::compute::<Bark, _>(&args, &*input);
+
+		assert!(output[0] > 0.0);
+		assert_eq!(&output[1 ..], &[0.0, 0.0, 0.0]);
+	}
+}
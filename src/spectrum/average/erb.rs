@@ -0,0 +1,80 @@
+use strided::{Stride, MutStride};
+
+use {Precision};
+use spectrum::average::Average;
+use spectrum::average::bank::{self, Band, Normalization};
+
+/// ERB critical-band average.
+///
+/// The arguments are the sample rate, the number of bands, the minimum and
+/// maximum frequency, the shape of the bands and their normalization.
+///
+/// The bands are equally spaced on the ERB-rate scale of Glasberg and Moore.
+pub struct Erb;
+
+impl Erb {
+	/// Converts the given frequency in hertz to ERB-rate.
+	#[inline]
+	pub fn erb(hz: Precision) -> Precision {
+		21.4 * (1.0 + 0.00437 * hz).log10()
+	}
+
+	/// Converts the given ERB-rate to a frequency in hertz.
+	#[inline]
+	pub fn hz(erb: Precision) -> Precision {
+		((10.0 as Precision).powf(erb / 21.4) - 1.0) / 0.00437
+	}
+
+	/// Computes the edges in hertz of the bands.
+	pub fn edges(&(_, bands, min, max, band, _): &<Self as Average>::Arguments) -> Vec<Precision> {
+		bank::edges(Erb::erb, Erb::hz, band, bands, min, max)
+	}
+}
+
+impl Average for Erb {
+	type Arguments = (u32, usize, u32, u32, Band, Normalization);
+
+	#[inline]
+	fn size(&(_, bands, _, _, _, _): &Self::Arguments) -> usize {
+		bands
+	}
+
+	fn compute(args: &Self::Arguments, input: Stride<Precision>, output: MutStride<Precision>) {
+		let &(rate, bands, min, max, band, normalization) = args;
+
+		debug_assert!(min < max && max <= rate / 2);
+		debug_assert_eq!(output.len(), bands);
+
+		bank::compute(&Erb::edges(args), band, normalization, rate, input, output);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::Erb;
+	use spectrum::average::{self, Band, Normalization};
+	use Precision;
+
+	macro_rules! assert_approx_eq {
+		($a:expr, $b:expr, $p:expr) => (
+			assert_eq!(format!("{:.1$}", $a, $p), format!("{:.1$}", $b, $p));
+		)
+	}
+
+	#[test]
+	fn scale() {
+		assert_approx_eq!(Erb::erb(1000.0), 15.62, 2);
+		assert_approx_eq!(Erb::hz(Erb::erb(4000.0)), 4000.0, 0);
+	}
+
+	#[test]
+	fn rectangular() {
+		// every bin belongs to a single band, so a flat spectrum adds up to the
+		// number of bins
+		let args   = (8000, 8, 0, 4000, Band::Rectangular, Normalization::None);
+		let input  = vec![1.0; 257];
+		let output = average::compute::<Erb, _>(&args, &*input);
+
+		assert_eq!(output.iter().sum::<Precision>() as usize, 257);
+	}
+}
@@ -0,0 +1,204 @@
+use strided::{Stride, MutStride, MutStrided};
+
+use {Precision};
+use spectrum::average::Average;
+use spectrum::bandwidth;
+
+/// The octave ratio used to compute the band frequencies.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Base {
+	/// Base-10 ratio, `10^(3/10)`, the one preferred by IEC 61260.
+	Ten,
+
+	/// Base-2 ratio, an exact octave.
+	Two,
+}
+
+impl Base {
+	/// The octave ratio.
+	#[inline]
+	pub fn ratio(&self) -> Precision {
+		match *self {
+			Base::Ten => (10.0 as Precision).powf(0.3),
+			Base::Two => 2.0,
+		}
+	}
+}
+
+/// The reference frequency all bands are centered around.
+const REFERENCE: Precision = 1000.0;
+
+/// Fractional-octave band average conforming to IEC 61260.
+///
+/// The arguments are the sample rate, the bandwidth designator (`1` for
+/// octaves, `3` for third-octaves, then `6`, `12` and `24`), the minimum and
+/// maximum center frequency and the octave ratio.
+///
+/// The output of `compute` is the band power, the sum of the squared magnitudes
+/// of the bins within each band, the band centers are given by `centers` and
+/// `bands` gives the centers alongside the levels in decibels.
+pub struct FractionalOctave;
+
+impl FractionalOctave {
+	/// Computes the exact center frequency of the band with the given index,
+	/// band `0` is centered on 1 kHz for odd designators and right above it for
+	/// even ones.
+	pub fn center(fraction: u32, base: Base, index: i32) -> Precision {
+		let ratio    = base.ratio();
+		let fraction = fraction as Precision;
+
+		if fraction as u32 % 2 == 1 {
+			REFERENCE * ratio.powf(index as Precision / fraction)
+		}
+		else {
+			REFERENCE * ratio.powf((2 * index + 1) as Precision / (2.0 * fraction))
+		}
+	}
+
+	/// Computes the lower and upper edge of the band with the given center.
+	pub fn edges(fraction: u32, base: Base, center: Precision) -> (Precision, Precision) {
+		let factor = base.ratio().powf(1.0 / (2.0 * fraction as Precision));
+
+		(center / factor, center * factor)
+	}
+
+	/// Computes the indices of the bands with a center between the minimum and
+	/// maximum frequency.
+	pub fn indices(&(_, fraction, min, max, base): &<Self as Average>::Arguments) -> ::std::ops::Range<i32> {
+		let ratio    = base.ratio();
+		let fraction = fraction as Precision;
+
+		let index = |frequency: u32| {
+			let exponent = (frequency as Precision / REFERENCE).ln() / ratio.ln() * fraction;
+
+			if fraction as u32 % 2 == 1 {
+				exponent
+			}
+			else {
+				exponent - 0.5
+			}
+		};
+
+		// tolerate rounding, band centers are not exact
+		let low  = (index(min) - 1e-3).ceil() as i32;
+		let high = (index(max) + 1e-3).floor() as i32;
+
+		low .. high + 1
+	}
+
+	/// Computes the exact center frequencies of the bands.
+	pub fn centers(args: &<Self as Average>::Arguments) -> Vec<Precision> {
+		let &(_, fraction, _, _, base) = args;
+
+		FractionalOctave::indices(args)
+			.map(|index| FractionalOctave::center(fraction, base, index))
+			.collect()
+	}
+
+	/// Computes the band centers alongside their level in decibels, `10 log10`
+	/// of the band power.
+	pub fn bands(args: &<Self as Average>::Arguments, input: Stride<Precision>) -> Vec<(Precision, Precision)> {
+		let mut output = vec![0.0; FractionalOctave::size(args)];
+		FractionalOctave::compute(args, input, output.as_stride_mut());
+
+		FractionalOctave::centers(args).into_iter()
+			.zip(output.into_iter().map(|power| 10.0 * power.max(Precision::MIN_POSITIVE).log10()))
+			.collect()
+	}
+}
+
+impl Average for FractionalOctave {
+	type Arguments = (u32, u32, u32, u32, Base);
+
+	#[inline]
+	fn size(args: &Self::Arguments) -> usize {
+		FractionalOctave::indices(args).len()
+	}
+
+	fn compute(args: &Self::Arguments, input: Stride<Precision>, mut output: MutStride<Precision>) {
+		let &(rate, fraction, min, max, base) = args;
+
+		debug_assert!(fraction > 0);
+		debug_assert!(min > 0 && min < max);
+		debug_assert_eq!(output.len(), FractionalOctave::size(args));
+
+		let bandwidth = bandwidth((input.len() - 1) * 2, rate);
+
+		for (center, output) in FractionalOctave::centers(args).into_iter().zip(output.iter_mut()) {
+			let (low, high) = FractionalOctave::edges(fraction, base, center);
+
+			*output = input.iter().enumerate()
+				.filter(|&(bin, _)| {
+					let frequency = bin as Precision * bandwidth;
+
+					frequency >= low && frequency < high
+				})
+				.map(|(_, value)| value * value)
+				.sum();
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use strided::Strided;
+	use super::{FractionalOctave, Base};
+	use spectrum::average;
+
+	#[test]
+	fn centers() {
+		let centers = FractionalOctave::centers(&(48000, 1, 31, 16000, Base::Ten))
+			.into_iter().map(|c| c.round() as u32).collect::<Vec<_>>();
+
+		assert_eq!(centers, [32, 63, 126, 251, 501, 1000, 1995, 3981, 7943, 15849]);
+
+		let centers = FractionalOctave::centers(&(48000, 3, 790, 1300, Base::Two))
+			.into_iter().map(|c| c.round() as u32).collect::<Vec<_>>();
+
+		assert_eq!(centers, [794, 1000, 1260]);
+	}
+
+	#[test]
+	fn even() {
+		let centers = FractionalOctave::centers(&(48000, 6, 900, 1200, Base::Ten))
+			.into_iter().map(|c| c.round() as u32).collect::<Vec<_>>();
+
+		assert_eq!(centers, [944, 1059, 1189]);
+	}
+
+	#[test]
+	fn compute() {
+		let args   = (8000, 1, 125, 2000, Base::Two);
+		let input  = vec![1.0; 513];
+		let output = average::compute::<FractionalOctave, _>(&args, &*input);
+
+		assert_eq!(output.len(), 5);
+
+		// every octave is twice as wide as the previous one
+		for pair in output.windows(2) {
+			assert!((pair[1] / pair[0] - 2.0).abs() < 0.2);
+		}
+	}
+
+	#[test]
+	fn bands() {
+		let     args  = (8000, 1, 125, 2000, Base::Two);
+		let mut input = vec![0.0; 513];
+
+		// a single bin at 1 kHz with a magnitude of 10
+		input[128] = 10.0;
+
+		let bands = FractionalOctave::bands(&args, input.as_stride());
+
+		assert_eq!(bands.len(), 5);
+
+		for &(center, level) in &bands {
+			if center == 1000.0 {
+				assert!((level - 20.0).abs() < 1e-4);
+			}
+			else {
+				assert!(level < -300.0);
+			}
+		}
+	}
+}
@@ -26,7 +26,7 @@ impl Average for Logarithmic {
 		let mut nyquist = rate / 2;
 		let mut octaves = 1u32;
 
-		while nyquist / 2 < min {
+		while nyquist / 2 > min {
 			octaves += 1;
 			nyquist /= 2;
 		}
@@ -36,26 +36,28 @@ impl Average for Logarithmic {
 				0
 			}
 			else {
-				(rate / 2) / (octaves - i).pow(2)
+				(rate / 2) / 2u32.pow(octaves - i)
 			};
 
-			let hig  = (rate / 2) / (octaves - i - 1).pow(2);
+			let hig  = (rate / 2) / 2u32.pow(octaves - i - 1);
 			let step = (hig - low) / bands;
 
 			let mut f = low;
 
 			for j in 0 .. bands {
 				let offset = j + i * bands;
-				let low    = index_for(f as u32,        input.len(), rate);
-				let hig    = index_for(f as u32 + step, input.len(), rate);
-
-				let mut average = 0.0;
+				let low    = index_for(f,        (input.len() - 1) * 2, rate);
+				let hig    = index_for(f + step, (input.len() - 1) * 2, rate);
 
-				for i in low .. hig {
-					average += input[i];
+				// bands narrower than a bin take the value of the bin they fall in
+				let average = if hig > low {
+					(low .. hig).map(|i| input[i]).sum::<Precision>() / (hig - low) as Precision
 				}
+				else {
+					input[low]
+				};
 
-				output[offset as usize]  = average / (hig - low + 1) as Precision;
+				output[offset as usize]  = average;
 				f                       += step;
 			}
 		}
@@ -0,0 +1,138 @@
+use strided::{Stride, MutStride};
+
+use {Precision};
+use spectrum::average::Average;
+use spectrum::average::bank::{self, Band, Normalization};
+
+/// The formula used to convert between hertz and mels.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Formula {
+	/// The HTK formula, logarithmic over the whole range.
+	Htk,
+
+	/// The Slaney formula (Auditory Toolbox), linear below 1 kHz and logarithmic
+	/// above.
+	Slaney,
+}
+
+const SLANEY_STEP: Precision = 200.0 / 3.0;
+const SLANEY_MIN:  Precision = 1000.0;
+
+impl Formula {
+	/// Converts the given frequency in hertz to mels.
+	pub fn mel(&self, hz: Precision) -> Precision {
+		match *self {
+			Formula::Htk =>
+				2595.0 * (1.0 + hz / 700.0).log10(),
+
+			Formula::Slaney if hz < SLANEY_MIN =>
+				hz / SLANEY_STEP,
+
+			Formula::Slaney =>
+				SLANEY_MIN / SLANEY_STEP + (hz / SLANEY_MIN).ln() / ((6.4 as Precision).ln() / 27.0),
+		}
+	}
+
+	/// Converts the given mels to a frequency in hertz.
+	pub fn hz(&self, mel: Precision) -> Precision {
+		match *self {
+			Formula::Htk =>
+				700.0 * ((10.0 as Precision).powf(mel / 2595.0) - 1.0),
+
+			Formula::Slaney if mel < SLANEY_MIN / SLANEY_STEP =>
+				mel * SLANEY_STEP,
+
+			Formula::Slaney =>
+				SLANEY_MIN * (((6.4 as Precision).ln() / 27.0) * (mel - SLANEY_MIN / SLANEY_STEP)).exp(),
+		}
+	}
+}
+
+/// Mel filterbank average.
+///
+/// The arguments are the sample rate, the number of filters, the minimum and
+/// maximum frequency, the mel formula and the normalization of the filters.
+///
+/// The filters are triangular and overlapping, each one spans from the center
+/// of the previous filter to the center of the next.
+pub struct Mel;
+
+impl Mel {
+	/// Computes the edges in hertz of the filters, filter `i` rises from edge `i`
+	/// to edge `i + 1` and falls to edge `i + 2`.
+	pub fn edges(&(_, filters, min, max, formula, _): &<Self as Average>::Arguments) -> Vec<Precision> {
+		bank::edges(|hz| formula.mel(hz), |mel| formula.hz(mel), Band::Triangular, filters, min, max)
+	}
+
+	/// Computes the weight of every bin in the spectrum of the given size for
+	/// the given filter.
+	pub fn weights(args: &<Self as Average>::Arguments, filter: usize, bins: usize) -> Vec<Precision> {
+		let &(rate, _, _, _, _, normalization) = args;
+
+		bank::weights(&Mel::edges(args), Band::Triangular, normalization, filter, rate, bins)
+	}
+}
+
+impl Average for Mel {
+	type Arguments = (u32, usize, u32, u32, Formula, Normalization);
+
+	#[inline]
+	fn size(&(_, filters, _, _, _, _): &Self::Arguments) -> usize {
+		filters
+	}
+
+	fn compute(args: &Self::Arguments, input: Stride<Precision>, output: MutStride<Precision>) {
+		let &(rate, filters, min, max, _, normalization) = args;
+
+		debug_assert!(min < max && max <= rate / 2);
+		debug_assert_eq!(output.len(), filters);
+
+		bank::compute(&Mel::edges(args), Band::Triangular, normalization, rate, input, output);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{Mel, Formula, Normalization};
+	use spectrum::average;
+	use Precision;
+
+	macro_rules! assert_approx_eq {
+		($a:expr, $b:expr, $p:expr) => (
+			assert_eq!(format!("{:.1$}", $a, $p), format!("{:.1$}", $b, $p));
+		)
+	}
+
+	#[test]
+	fn formula() {
+		assert_approx_eq!(Formula::Htk.mel(1000.0), 1000.0, 0);
+		assert_approx_eq!(Formula::Htk.hz(1000.0), 1000.0, 0);
+		assert_approx_eq!(Formula::Slaney.mel(1000.0), 15.0, 2);
+		assert_approx_eq!(Formula::Slaney.mel(500.0), 7.5, 2);
+		assert_approx_eq!(Formula::Slaney.hz(Formula::Slaney.mel(4000.0)), 4000.0, 0);
+	}
+
+	#[test]
+	fn compute() {
+		let args = (8000, 3, 0, 4000, Formula::Htk, Normalization::None);
+
+		// a flat spectrum gets the sum of the filter weights
+		let input  = vec![1.0; 257];
+		let output = average::compute::<Mel, _>(&args, &*input);
+
+		assert_eq!(output.len(), 3);
+
+		for (filter, output) in output.iter().enumerate() {
+			let weights: Precision = Mel::weights(&args, filter, 257).iter().sum();
+			assert_approx_eq!(*output, weights, 3);
+		}
+
+		// a single bin only lights up the filters around it
+		let mut input = vec![0.0; 257];
+		input[16] = 1.0;
+
+		let output = average::compute::<Mel, _>(&args, &*input);
+		assert!(output[0] > 0.0);
+		assert_eq!(output[2], 0.0);
+	}
+}
@@ -4,6 +4,21 @@ pub use self::linear::Linear;
 mod logarithmic;
 pub use self::logarithmic::Logarithmic;
 
+mod bank;
+pub use self::bank::{Band, Normalization};
+
+mod mel;
+pub use self::mel::{Mel, Formula};
+
+mod bark;
+pub use self::bark::Bark;
+
+mod erb;
+pub use self::erb::Erb;
+
+mod fractional_octave;
+pub use self::fractional_octave::{FractionalOctave, Base};
+
 use {Precision};
 use strided::{Strided, MutStrided, Stride, MutStride};
 
@@ -19,7 +34,7 @@ pub trait Average {
 	/// ouotput.
 	fn compute(args: &Self::Arguments,
 	           input: Stride<Precision>,
-	           mut output: MutStride<Precision>);
+	           output: MutStride<Precision>);
 }
 
 /// Get the size of the output for the given algorithm.
@@ -54,11 +69,45 @@ pub fn compute_in<A, I, O>(args: &A::Arguments, input: I, mut output: O)
 
 #[cfg(test)]
 mod tests {
-	use super::{Linear, Logarithmic};
+	use super::{Linear, Logarithmic, Mel, Bark, Erb, FractionalOctave};
+	use super::{Formula, Band, Normalization, Base};
+	use Precision;
 
 	#[test]
 	fn size() {
 		assert_eq!(super::size::<Linear>(&3), 3);
 		assert_eq!(super::size::<Logarithmic>(&(44100, 11, 1)), 11);
+		assert_eq!(super::size::<Mel>(&(44100, 40, 0, 22050, Formula::Htk, Normalization::None)), 40);
+		assert_eq!(super::size::<Bark>(&(44100, 24, 20, 15500, Band::Rectangular, Normalization::None)), 24);
+		assert_eq!(super::size::<Erb>(&(44100, 32, 50, 20000, Band::Triangular, Normalization::Area)), 32);
+		assert_eq!(super::size::<FractionalOctave>(&(44100, 3, 25, 20000, Base::Ten)), 30);
+	}
+
+	#[test]
+	fn logarithmic() {
+		// a ramp, so every average depends on the edges of its band
+		let input  = (0 .. 513).map(|i| i as Precision).collect::<Vec<_>>();
+		let output = super::compute::<Logarithmic, _>(&(44100, 11, 1), &*input);
+
+		assert_eq!(output.len(), 11);
+
+		// the octaves end at 43, 86, ... 22050 Hz, the bins are 43 Hz wide
+		let edges = [0, 0, 1, 2, 4, 8, 16, 32, 64, 128, 256, 512];
+
+		// the mean of a ramp is the middle of the band, an empty band takes the
+		// value of its bin
+		for (value, edge) in output.iter().zip(edges.windows(2)) {
+			let (low, high) = (edge[0] as Precision, edge[1] as Precision);
+			let expected    = if high > low { (low + high - 1.0) / 2.0 } else { low };
+
+			assert!((value - expected).abs() < 1e-3, "{} {}", value, expected);
+		}
+
+		// bands subdivide octaves linearly
+		let output = super::compute::<Logarithmic, _>(&(44100, 11, 2), &*input);
+
+		assert_eq!(output.len(), 22);
+		assert!((output[20] - 319.5).abs() < 1e-3);
+		assert!((output[21] - 447.5).abs() < 1e-3);
 	}
 }
@@ -4,6 +4,14 @@ pub use self::average::Average;
 pub use self::average::compute as average;
 pub use self::average::compute_in as average_in;
 
+/// Sub-bin spectral peak estimation.
+pub mod peak;
+
+/// Welch power and cross spectral density estimation.
+pub mod welch;
+
+use std::f64::consts::PI;
+
 use {Precision, Complex};
 use strided::{Strided, MutStrided};
 
@@ -56,6 +64,45 @@ pub fn bandwidth(size: usize, rate: u32) -> Precision {
 	(2.0 / size as Precision) * (rate as Precision / 2.0)
 }
 
+/// Computes the unwrapped phase of the given input and returns a vector with
+/// the computed values.
+#[inline(always)]
+pub fn unwrap<C, I>(input: I) -> Vec<Precision>
+	where C: Complex,
+	      I: Strided<Elem=C>
+{
+	let mut output = vec![0.0; input.as_stride().len()];
+	unwrap_in(input, &mut *output);
+
+	output
+}
+
+/// Computes the unwrapped phase of the given input into the given output,
+/// jumps between consecutive values are kept within `π`.
+pub fn unwrap_in<C, I, O>(input: I, mut output: O)
+	where C: Complex,
+	      I: Strided<Elem=C>,
+	      O: MutStrided<Elem=Precision>
+{
+	let     input    = input.as_stride();
+	let mut output   = output.as_stride_mut();
+	let mut previous = 0.0;
+	let mut offset   = 0.0;
+
+	debug_assert_eq!(input.len(), output.len());
+
+	for (index, (input, output)) in input.iter().zip(output.iter_mut()).enumerate() {
+		let angle = (input.imag() as f64).atan2(input.real() as f64);
+
+		if index > 0 {
+			offset -= ((angle + offset - previous) / (2.0 * PI)).round() * 2.0 * PI;
+		}
+
+		previous = angle + offset;
+		*output  = previous as Precision;
+	}
+}
+
 /// Gets the index for the given frequency in a window of the given size and
 /// sample rate.
 pub fn index_for(frequency: u32, size: usize, rate: u32) -> usize {
@@ -75,20 +122,35 @@ pub fn index_for(frequency: u32, size: usize, rate: u32) -> usize {
 /// Returns the frequency for the given index in a window of the given size and
 /// sample rate.
 pub fn frequency_for(index: usize, size: usize, rate: u32) -> u32 {
+	let frequency = frequency(index, size, rate);
+
+	if index == 0 || index >= size / 2 {
+		return frequency.round() as u32;
+	}
+
+	frequency as u32
+}
+
+/// Returns the exact frequency for the given index in a window of the given
+/// size and sample rate.
+///
+/// The first and last bins only cover half a band, so they are mapped to the
+/// middle of the half they cover.
+pub fn frequency(index: usize, size: usize, rate: u32) -> Precision {
 	let bandwidth = bandwidth(size, rate);
 
 	if index == 0 {
-		return (bandwidth * 0.25).round() as u32;
+		return bandwidth * 0.25;
 	}
 
 	if index >= size / 2 {
 		let last = (rate as Precision / 2.0) - (bandwidth / 2.0);
 		let half = bandwidth * 0.25;
 
-		return (last + half).round() as u32;
+		return last + half;
 	}
 
-	(index as Precision * bandwidth as Precision) as u32
+	index as Precision * bandwidth
 }
 
 #[cfg(test)]
@@ -110,4 +172,14 @@ mod tests {
 		assert_eq!(super::frequency_for(256, 1024, 44_100), 11025);
 		assert_eq!(super::frequency_for(512, 1024, 44_100), 22039);
 	}
+
+	#[test]
+	fn unwrap() {
+		let input = (0 .. 20).map(|n| ::num::Complex::from_polar(&1.0, &(-0.9 * n as ::Precision)))
+			.collect::<Vec<_>>();
+
+		for (n, &phase) in super::unwrap(&*input).iter().enumerate() {
+			assert!((phase + 0.9 * n as ::Precision).abs() < 1e-5);
+		}
+	}
 }
@@ -0,0 +1,258 @@
+use std::f64::consts::PI;
+use num;
+use strided::{Strided, Stride};
+
+use {Precision, Sample, Complex};
+use spectrum::bandwidth;
+
+/// The estimators used to interpolate the position of a peak between bins.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Estimator {
+	/// A parabola fitted through the magnitudes of the peak and its neighbours.
+	Parabolic,
+
+	/// A parabola fitted through the log magnitudes, exact for Gaussian windows
+	/// and close for most smooth windows.
+	Gaussian,
+
+	/// Quinn's second estimator on the complex values of the peak and its
+	/// neighbours.
+	Quinn,
+
+	/// Jain's estimator on the magnitude of the peak and its larger neighbour.
+	Jain,
+}
+
+/// A spectral peak.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct Peak {
+	/// The fractional bin of the peak.
+	pub bin: Precision,
+
+	/// The frequency of the peak in hertz.
+	pub frequency: Precision,
+
+	/// The amplitude of the sinusoid producing the peak.
+	pub amplitude: Precision,
+}
+
+/// Finds the local maxima of the given spectrum as returned by `forward` with
+/// a magnitude above the given fraction of the largest one, and interpolates
+/// them.
+///
+/// The window is the one the input was multiplied by before the transform,
+/// its length is the size of the transform, so either the full or the
+/// positive half of the spectrum can be given.
+pub fn detect<C, S, I, W>(estimator: Estimator, input: I, window: W, rate: u32, threshold: Precision) -> Vec<Peak>
+	where C: Complex,
+	      S: Sample,
+	      I: Strided<Elem=C>,
+	      W: Strided<Elem=S>
+{
+	let input  = input.as_stride();
+	let window = window.as_stride();
+	let half   = window.len() / 2;
+
+	debug_assert!(input.len() > half);
+
+	let magnitude = (0 .. half + 1).map(|bin| input[bin].to_num().norm()).collect::<Vec<_>>();
+	let max       = magnitude.iter().fold(0.0, |max: Precision, &m| max.max(m));
+
+	if max == 0.0 {
+		return Vec::new();
+	}
+
+	(1 .. half)
+		.filter(|&bin| magnitude[bin] > magnitude[bin - 1] && magnitude[bin] >= magnitude[bin + 1])
+		.filter(|&bin| magnitude[bin] >= max * threshold)
+		.map(|bin| interpolate(estimator, input, window, bin, rate))
+		.collect()
+}
+
+/// Interpolates the peak at the given bin of the given spectrum as returned
+/// by `forward`.
+///
+/// The bias of the estimator for the given window is removed by inverting its
+/// response to an ideal sinusoid, and the amplitude is corrected by the window
+/// gain at the estimated offset.
+pub fn interpolate<C, S, I, W>(estimator: Estimator, input: I, window: W, bin: usize, rate: u32) -> Peak
+	where C: Complex,
+	      S: Sample,
+	      I: Strided<Elem=C>,
+	      W: Strided<Elem=S>
+{
+	let input  = input.as_stride();
+	let window = window.as_stride();
+	let size   = window.len();
+
+	debug_assert!(bin > 0 && bin < size / 2);
+
+	let raw = estimate(estimator,
+		input[bin - 1].to_num(), input[bin].to_num(), input[bin + 1].to_num());
+
+	// the estimator's response to a sinusoid at the given offset
+	let response = |offset: Precision|
+		estimate(estimator,
+			kernel(window, -1.0 - offset),
+			kernel(window, -offset),
+			kernel(window, 1.0 - offset));
+
+	// the response is monotonic, so invert it by bisection
+	let mut low  = -0.5 as Precision;
+	let mut high =  0.5 as Precision;
+
+	if raw <= response(low) {
+		high = low;
+	}
+	else if raw >= response(high) {
+		low = high;
+	}
+	else {
+		for _ in 0 .. 32 {
+			let middle = (low + high) / 2.0;
+
+			if response(middle) < raw {
+				low = middle;
+			}
+			else {
+				high = middle;
+			}
+		}
+	}
+
+	let offset = (low + high) / 2.0;
+	let gain   = kernel(window, -offset).norm();
+
+	Peak {
+		bin:       bin as Precision + offset,
+		frequency: (bin as Precision + offset) * bandwidth(size, rate),
+		amplitude: if gain > 0.0 { 2.0 * input[bin].to_num().norm() / gain } else { 0.0 },
+	}
+}
+
+/// Computes the raw offset from the middle bin with the given estimator.
+fn estimate(estimator: Estimator, left: num::Complex<Precision>, center: num::Complex<Precision>, right: num::Complex<Precision>) -> Precision {
+	let offset = match estimator {
+		Estimator::Parabolic =>
+			parabolic(left.norm(), center.norm(), right.norm()),
+
+		Estimator::Gaussian => {
+			let floor = Precision::MIN_POSITIVE;
+
+			parabolic(left.norm().max(floor).ln(), center.norm().max(floor).ln(), right.norm().max(floor).ln())
+		}
+
+		Estimator::Quinn => {
+			if center.norm() == 0.0 {
+				return 0.0;
+			}
+
+			let tau = |x: Precision| {
+				let root = (2.0 as Precision / 3.0).sqrt();
+
+				0.25 * (3.0 * x * x + 6.0 * x + 1.0).ln()
+					- (6.0 as Precision).sqrt() / 24.0 * ((x + 1.0 - root) / (x + 1.0 + root)).ln()
+			};
+
+			let ap = (right / center).re;
+			let am = (left / center).re;
+			let dp = -ap / (1.0 - ap);
+			let dm =  am / (1.0 - am);
+
+			(dp + dm) / 2.0 + tau(dp * dp) - tau(dm * dm)
+		}
+
+		Estimator::Jain => {
+			let (left, center, right) = (left.norm(), center.norm(), right.norm());
+
+			if right > left {
+				right / (center + right)
+			}
+			else if left + center > 0.0 {
+				-left / (left + center)
+			}
+			else {
+				0.0
+			}
+		}
+	};
+
+	if offset.is_finite() { offset } else { 0.0 }
+}
+
+/// Computes the offset of the vertex of the parabola through three equally
+/// spaced points from the middle one.
+pub fn parabolic(left: Precision, center: Precision, right: Precision) -> Precision {
+	let denominator = left - 2.0 * center + right;
+
+	if denominator == 0.0 {
+		return 0.0;
+	}
+
+	0.5 * (left - right) / denominator
+}
+
+/// Computes the transform of the window at the given fractional bin.
+fn kernel<S: Sample>(window: Stride<S>, bin: Precision) -> num::Complex<Precision> {
+	let size = window.len() as Precision;
+
+	window.iter().enumerate().fold(num::Complex::new(0.0, 0.0), |sum, (n, w)|
+		sum + num::Complex::from_polar(&w.normalize(), &(-2.0 * PI as Precision * bin * n as Precision / size)))
+}
+
+#[cfg(test)]
+mod tests {
+	use std::f64::consts::PI;
+	use num::Complex;
+	use super::Estimator;
+	use window::{self, Rectangular, Hann, Blackman, Symmetry};
+	use {Precision, forward};
+
+	fn tone<F: window::Function + 'static>(bin: Precision, amplitude: Precision) -> (Vec<Complex<Precision>>, Vec<Precision>) {
+		let window = window::generate::<F, Precision, _>(.., Symmetry::Periodic, 1024);
+		let input  = (0 .. 1024)
+			.map(|n| amplitude * (2.0 * PI as Precision * bin * n as Precision / 1024.0).cos() * window[n])
+			.collect::<Vec<_>>();
+
+		(forward(&*input), window.to_vec())
+	}
+
+	#[test]
+	fn estimators() {
+		for &estimator in &[Estimator::Parabolic, Estimator::Gaussian, Estimator::Quinn, Estimator::Jain] {
+			let (spectrum, window) = tone::<Rectangular>(100.3, 0.5);
+			let peak               = super::interpolate(estimator, &*spectrum, &*window, 100, 1024);
+
+			assert!((peak.bin - 100.3).abs() < 0.01, "{:?} {:?}", estimator, peak);
+			assert!((peak.amplitude - 0.5).abs() < 0.01, "{:?} {:?}", estimator, peak);
+		}
+	}
+
+	#[test]
+	fn window() {
+		let (spectrum, window) = tone::<Hann>(200.42, 1.0);
+		let peak               = super::interpolate(Estimator::Parabolic, &*spectrum, &*window, 200, 44100);
+
+		assert!((peak.bin - 200.42).abs() < 0.01);
+		assert!((peak.frequency - 200.42 * 44100.0 / 1024.0).abs() < 0.5);
+		assert!((peak.amplitude - 1.0).abs() < 0.01);
+	}
+
+	#[test]
+	fn detect() {
+		let window = window::generate::<Blackman, Precision, _>(.., Symmetry::Periodic, 1024);
+		let input  = (0 .. 1024).map(|n| {
+			let t = n as Precision / 1024.0;
+
+			((2.0 * PI as Precision * 50.25 * t).cos() + 0.25 * (2.0 * PI as Precision * 300.75 * t).cos()) * window[n]
+		}).collect::<Vec<_>>();
+
+		let spectrum: Vec<Complex<Precision>> = forward(&*input);
+		let peaks = super::detect(Estimator::Gaussian, &*spectrum, &**window, 1024, 0.01);
+
+		assert_eq!(peaks.len(), 2);
+		assert!((peaks[0].bin - 50.25).abs() < 0.01);
+		assert!((peaks[1].bin - 300.75).abs() < 0.01);
+		assert!((peaks[1].amplitude - 0.25).abs() < 0.01);
+	}
+}
@@ -0,0 +1,287 @@
+use num::{self, Zero};
+use strided::{Strided, Stride};
+
+use {Precision, Sample, forward_in};
+use window::{self, Function, Symmetry};
+
+/// Parameters for the Welch estimators.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct Parameters {
+	/// The sample rate of the inputs.
+	pub rate: u32,
+
+	/// The length of a segment in samples.
+	pub frame: usize,
+
+	/// The distance between the start of two segments in samples, the overlap
+	/// is `frame - hop`.
+	pub hop: usize,
+
+	/// The size of the FFT, segments are zero-padded to it.
+	pub size: usize,
+}
+
+impl Default for Parameters {
+	fn default() -> Self {
+		Parameters {
+			rate:  44100,
+			frame: 1024,
+			hop:   512,
+			size:  1024,
+		}
+	}
+}
+
+impl Parameters {
+	/// The number of bins of the one-sided estimates.
+	#[inline(always)]
+	pub fn bins(&self) -> usize {
+		self.size / 2 + 1
+	}
+}
+
+/// The transfer function estimator.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Estimator {
+	/// `Pxy / Pxx`, unbiased by noise on the output.
+	H1,
+
+	/// `Pyy / Pyx`, unbiased by noise on the input.
+	H2,
+}
+
+/// Estimates the one-sided power spectral density of the given input, in
+/// units squared per hertz, by averaging the periodograms of overlapping
+/// segments windowed with the given window function.
+pub fn psd<F, S, I>(parameters: &Parameters, input: I) -> Vec<Precision>
+	where F: Function + 'static,
+	      S: Sample,
+	      I: Strided<Elem=S>
+{
+	let input = input.as_stride();
+
+	estimate::<F, _, _>(parameters, input, input).0
+}
+
+/// Estimates the one-sided cross power spectral density `Pxy` of the given
+/// inputs of the same length, `X*·Y` averaged over the segments.
+pub fn csd<F, SX, SY, X, Y>(parameters: &Parameters, x: X, y: Y) -> Vec<num::Complex<Precision>>
+	where F:  Function + 'static,
+	      SX: Sample,
+	      SY: Sample,
+	      X:  Strided<Elem=SX>,
+	      Y:  Strided<Elem=SY>
+{
+	estimate::<F, _, _>(parameters, x.as_stride(), y.as_stride()).2
+}
+
+/// Estimates the magnitude-squared coherence `|Pxy|² / (Pxx Pyy)` of the given
+/// inputs of the same length.
+///
+/// The coherence of a single segment is always one, it needs several segments
+/// to be meaningful.
+pub fn coherence<F, SX, SY, X, Y>(parameters: &Parameters, x: X, y: Y) -> Vec<Precision>
+	where F:  Function + 'static,
+	      SX: Sample,
+	      SY: Sample,
+	      X:  Strided<Elem=SX>,
+	      Y:  Strided<Elem=SY>
+{
+	let (pxx, pyy, pxy) = estimate::<F, _, _>(parameters, x.as_stride(), y.as_stride());
+
+	pxx.iter().zip(pyy.iter()).zip(pxy.iter()).map(|((&pxx, &pyy), pxy)| {
+		let power = pxx * pyy;
+
+		if power <= Precision::MIN_POSITIVE {
+			0.0
+		}
+		else {
+			(pxy.norm_sqr() / power).min(1.0)
+		}
+	}).collect()
+}
+
+/// Estimates the transfer function from the given reference input `x` to the
+/// given measured output `y` of the same length.
+pub fn transfer<F, SX, SY, X, Y>(estimator: Estimator, parameters: &Parameters, x: X, y: Y) -> Vec<num::Complex<Precision>>
+	where F:  Function + 'static,
+	      SX: Sample,
+	      SY: Sample,
+	      X:  Strided<Elem=SX>,
+	      Y:  Strided<Elem=SY>
+{
+	let (pxx, pyy, pxy) = estimate::<F, _, _>(parameters, x.as_stride(), y.as_stride());
+
+	pxx.iter().zip(pyy.iter()).zip(pxy.iter()).map(|((&pxx, &pyy), &pxy)| {
+		match estimator {
+			Estimator::H1 if pxx > Precision::MIN_POSITIVE =>
+				pxy / pxx,
+
+			Estimator::H2 if pxy.norm_sqr() > Precision::MIN_POSITIVE =>
+				num::Complex::new(pyy, 0.0) / pxy.conj(),
+
+			_ =>
+				num::Complex::zero(),
+		}
+	}).collect()
+}
+
+/// Returns the frequencies of the bins of the estimates.
+pub fn frequencies(parameters: &Parameters) -> Vec<Precision> {
+	let step = parameters.rate as Precision / parameters.size as Precision;
+
+	(0 .. parameters.bins()).map(|k| k as Precision * step).collect()
+}
+
+/// Averages the auto and cross spectra of the segments of both inputs.
+///
+/// Only complete segments are used, an input shorter than a segment gives a
+/// single zero-padded one.
+fn estimate<F, SX, SY>(parameters: &Parameters, x: Stride<SX>, y: Stride<SY>)
+	-> (Vec<Precision>, Vec<Precision>, Vec<num::Complex<Precision>>)
+	where F:  Function + 'static,
+	      SX: Sample,
+	      SY: Sample
+{
+	let p = parameters;
+
+	debug_assert_eq!(x.len(), y.len());
+	debug_assert!(p.frame <= p.size && p.hop > 0);
+
+	let window = window::generate::<F, Precision, _>(.., Symmetry::Periodic, p.frame);
+	let bins   = p.bins();
+	let count  = if x.len() <= p.frame { 1 } else { (x.len() - p.frame) / p.hop + 1 };
+
+	let mut pxx = vec![0.0; bins];
+	let mut pyy = vec![0.0; bins];
+	let mut pxy = vec![num::Complex::<Precision>::zero(); bins];
+
+	let mut segment = vec![num::Complex::<Precision>::zero(); p.size];
+	let mut a       = vec![num::Complex::<Precision>::zero(); p.size];
+	let mut b       = vec![num::Complex::<Precision>::zero(); p.size];
+
+	for index in 0 .. count {
+		let offset = index * p.hop;
+
+		transform(&window, x, offset, &mut segment, &mut a);
+		transform(&window, y, offset, &mut segment, &mut b);
+
+		for (k, (a, b)) in a.iter().zip(b.iter()).take(bins).enumerate() {
+			pxx[k] += a.norm_sqr();
+			pyy[k] += b.norm_sqr();
+			pxy[k] += a.conj() * b;
+		}
+	}
+
+	// density scaling, with the negative frequencies folded in
+	let energy = window.iter().map(|w| w * w).sum::<Precision>();
+	let scale  = 1.0 / (p.rate as Precision * energy * count as Precision);
+
+	for (k, ((pxx, pyy), pxy)) in pxx.iter_mut().zip(pyy.iter_mut()).zip(pxy.iter_mut()).enumerate() {
+		let factor = if k == 0 || (p.size % 2 == 0 && k == p.size / 2) { scale } else { 2.0 * scale };
+
+		*pxx *= factor;
+		*pyy *= factor;
+		*pxy *= factor;
+	}
+
+	(pxx, pyy, pxy)
+}
+
+/// Transforms the windowed and zero-padded segment starting at the given
+/// offset.
+fn transform<S: Sample>(window: &[Precision], input: Stride<S>, offset: usize, segment: &mut [num::Complex<Precision>], output: &mut [num::Complex<Precision>]) {
+	for (n, value) in segment.iter_mut().enumerate() {
+		*value = match (window.get(n), input.get(offset + n)) {
+			(Some(w), Some(s)) => num::Complex::new(w * s.normalize(), 0.0),
+			_                  => num::Complex::zero(),
+		};
+	}
+
+	forward_in(&*segment, &mut *output);
+}
+
+#[cfg(test)]
+mod tests {
+	use std::f64::consts::PI;
+	use super::{Parameters, Estimator};
+	use window::Hann;
+	use filter::freqz_at;
+	use {Precision, Mode, convolve_real};
+
+	fn noise(length: usize, seed: u32) -> Vec<Precision> {
+		let mut state = seed;
+
+		(0 .. length).map(|_| {
+			state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+			(state >> 8) as Precision / (1 << 24) as Precision - 0.5
+		}).collect()
+	}
+
+	fn parameters() -> Parameters {
+		Parameters {
+			rate:  8000,
+			frame: 256,
+			hop:   128,
+			size:  256,
+		}
+	}
+
+	#[test]
+	fn psd() {
+		let p     = parameters();
+		let input = (0 .. 8000).map(|n| 2.0 * (2.0 * PI as Precision * 1000.0 * n as Precision / 8000.0).sin())
+			.collect::<Vec<_>>();
+
+		let density = super::psd::<Hann, _, _>(&p, &*input);
+		let step    = 8000.0 / 256.0;
+
+		assert_eq!(density.len(), 129);
+
+		// the density integrates to the power of the sine
+		assert!((density.iter().sum::<Precision>() * step - 2.0).abs() < 1e-2);
+
+		let peak = (0 .. 129).max_by(|&a, &b| density[a].partial_cmp(&density[b]).unwrap()).unwrap();
+		assert_eq!(super::frequencies(&p)[peak], 1000.0);
+	}
+
+	#[test]
+	fn transfer() {
+		let p        = parameters();
+		let response = [0.5, 0.3, -0.2, 0.1];
+		let x        = noise(32_000, 1);
+		let y        = convolve_real::<_, _, Precision, _, _>(&*x, &response[..], Mode::Full);
+		let y        = &y[.. x.len()];
+
+		let frequencies = super::frequencies(&p);
+		let expected    = freqz_at(&response[..], &[1.0][..], &frequencies, 8000);
+
+		for &estimator in &[Estimator::H1, Estimator::H2] {
+			let output = super::transfer::<Hann, _, _, _, _>(estimator, &p, &*x, y);
+
+			for (a, b) in output.iter().zip(expected.values().iter()) {
+				assert!((a - b).norm() < 1e-2, "{:?} {} {}", estimator, a, b);
+			}
+		}
+
+		let coherence = super::coherence::<Hann, _, _, _, _>(&p, &*x, y);
+		assert!(coherence.iter().all(|&c| c > 0.97));
+
+		// noise on the output lowers the coherence but doesn't bias H1
+		let other = noise(32_000, 2);
+		let noisy = y.iter().zip(other.iter()).map(|(y, n)| y + n).collect::<Vec<_>>();
+
+		let coherence = super::coherence::<Hann, _, _, _, _>(&p, &*x, &*noisy);
+		let mean      = coherence.iter().sum::<Precision>() / coherence.len() as Precision;
+		assert!(mean < 0.8);
+
+		let h1 = super::transfer::<Hann, _, _, _, _>(Estimator::H1, &p, &*x, &*noisy);
+		let h2 = super::transfer::<Hann, _, _, _, _>(Estimator::H2, &p, &*x, &*noisy);
+
+		let error = |h: &[::num::Complex<Precision>]| h.iter().zip(expected.values().iter())
+			.map(|(a, b)| (a.norm() - b.norm()).abs()).sum::<Precision>() / h.len() as Precision;
+
+		assert!(error(&h1) < 0.05);
+		assert!(error(&h2) > error(&h1) * 4.0);
+	}
+}
@@ -42,33 +42,47 @@ fn fft<CI: Complex, CO: ComplexMut>(direction: Precision, input: Stride<CI>, mut
 	}
 
 	// do the convultion
-	{
-		// temporary buffer for the juggling
-		let mut tmp = vec![num::Complex::<Precision>::zero(); next];
+	convolve(&mut a, &mut b);
 
-		// forward FFT on b (tmp)
-		ct::forward(b.as_stride(), tmp.as_stride_mut());
+	for (i, output) in output.iter_mut().enumerate() {
+		output.set(&a[i]);
+	}
 
-		// forward FFT on a (b)
-		ct::forward(a.as_stride(), b.as_stride_mut());
+	// postprocessing
+	for (output, exp) in output.iter_mut().zip(t.iter()) {
+		output.mul(exp);
+	}
+}
 
-		// multiply a (b) with b (tmp)
-		for i in 0 .. next {
-			b[i].mul(&tmp[i]);
-		}
+/// Computes the circular convolution of the two given buffers, whose length
+/// must be a power of two, and puts the scaled result in the first one.
+#[doc(hidden)]
+pub fn convolve(a: &mut [num::Complex<Precision>], b: &mut [num::Complex<Precision>]) {
+	debug_assert_eq!(a.len(), b.len());
+	debug_assert!(a.len().is_power_of_two());
 
-		// inverse FFT on a (b)
-		ct::inverse(b.as_stride(), a.as_stride_mut());
+	let next = a.len();
 
-		// scale and set the output
-		for (i, output) in output.iter_mut().enumerate() {
-			output.set(&a[i].unscale(next as Precision));
-		}
+	// temporary buffer for the juggling
+	let mut tmp = vec![num::Complex::<Precision>::zero(); next];
+
+	// forward FFT on b (tmp)
+	ct::forward(b.as_stride(), tmp.as_stride_mut());
+
+	// forward FFT on a (b)
+	ct::forward(a.as_stride(), b.as_stride_mut());
+
+	// multiply a (b) with b (tmp)
+	for i in 0 .. next {
+		b[i].mul(&tmp[i]);
 	}
 
-	// postprocessing
-	for (output, exp) in output.iter_mut().zip(t.iter()) {
-		output.mul(exp);
+	// inverse FFT on a (b)
+	ct::inverse(b.as_stride(), a.as_stride_mut());
+
+	// scale
+	for value in a.iter_mut() {
+		value.unscale(next as Precision);
 	}
 }
 
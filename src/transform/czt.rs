@@ -0,0 +1,184 @@
+use std::f64::consts::PI;
+use num::{self, Zero};
+use strided::{Strided, MutStrided};
+
+use {Precision, Complex, ComplexMut};
+use super::bluestein::convolve;
+
+/// Computes `base^(exponent)` in double precision, the chirps have quadratic
+/// exponents so single precision loses the phase quickly.
+#[inline]
+fn power(base: num::Complex<f64>, exponent: f64) -> num::Complex<Precision> {
+	let (radius, angle) = base.to_polar();
+	let value           = num::Complex::from_polar(&radius.powf(exponent), &(angle * exponent));
+
+	num::Complex::new(value.re as Precision, value.im as Precision)
+}
+
+/// Computes the chirp Z-transform of the given input along the spiral
+/// starting at `a` with ratio `w` and returns the given number of points.
+///
+/// The output is `X[k] = Σ x[n] a^-n w^nk`, so `a = 1` and `w = e^(-2πi/N)`
+/// with `N` points gives the DFT.
+#[inline(always)]
+pub fn compute<CI, CO, I>(input: I, points: usize, a: num::Complex<Precision>, w: num::Complex<Precision>) -> Vec<CO>
+	where CI: Complex,
+	      CO: ComplexMut,
+	      I:  Strided<Elem=CI>
+{
+	let mut output = vec![CO::zero(); points];
+	compute_in(input, a, w, &mut *output);
+
+	output
+}
+
+/// Computes the chirp Z-transform of the given input along the spiral
+/// starting at `a` with ratio `w` into the given output, the number of points
+/// is the length of the output.
+pub fn compute_in<CI, CO, I, O>(input: I, a: num::Complex<Precision>, w: num::Complex<Precision>, mut output: O)
+	where CI: Complex,
+	      CO: ComplexMut,
+	      I:  Strided<Elem=CI>,
+	      O:  MutStrided<Elem=CO>
+{
+	let     input  = input.as_stride();
+	let mut output = output.as_stride_mut();
+
+	let length = input.len();
+	let points = output.len();
+
+	if length == 0 || points == 0 {
+		return;
+	}
+
+	let a    = num::Complex::new(a.re as f64, a.im as f64);
+	let w    = num::Complex::new(w.re as f64, w.im as f64);
+	let next = (length + points - 1).next_power_of_two();
+
+	// nk = (n² + k² - (k - n)²) / 2 turns the sum into a convolution
+	let mut x = vec![num::Complex::<Precision>::zero(); next];
+	let mut y = vec![num::Complex::<Precision>::zero(); next];
+
+	for (n, value) in input.iter().enumerate() {
+		let t = n as f64;
+
+		x[n] = value.to_num() * power(a, -t) * power(w, t * t / 2.0);
+	}
+
+	for (j, value) in y[.. points].iter_mut().enumerate() {
+		*value = power(w, -((j * j) as f64) / 2.0);
+	}
+
+	for j in 1 .. length {
+		y[next - j] = power(w, -((j * j) as f64) / 2.0);
+	}
+
+	convolve(&mut x, &mut y);
+
+	for (k, output) in output.iter_mut().enumerate() {
+		output.set(&(x[k] * power(w, (k * k) as f64 / 2.0)));
+	}
+}
+
+/// Computes the spectrum of the given input on the given number of points
+/// equally spaced in the band starting at `from` up to, but excluding, `to`,
+/// both in hertz.
+///
+/// The resolution only depends on the number of points, so a narrow band can
+/// be analyzed in detail without a huge transform.
+#[inline(always)]
+pub fn zoom<CI, CO, I>(input: I, from: Precision, to: Precision, rate: u32, points: usize) -> Vec<CO>
+	where CI: Complex,
+	      CO: ComplexMut,
+	      I:  Strided<Elem=CI>
+{
+	let mut output = vec![CO::zero(); points];
+	zoom_in(input, from, to, rate, &mut *output);
+
+	output
+}
+
+/// Computes the spectrum of the given input in the band starting at `from` up
+/// to, but excluding, `to` into the given output.
+pub fn zoom_in<CI, CO, I, O>(input: I, from: Precision, to: Precision, rate: u32, output: O)
+	where CI: Complex,
+	      CO: ComplexMut,
+	      I:  Strided<Elem=CI>,
+	      O:  MutStrided<Elem=CO>
+{
+	debug_assert!(from < to);
+
+	let points = output.as_stride().len() as f64;
+	let rate   = rate as f64;
+	let step   = (to - from) as f64 / points;
+
+	let a = num::Complex::from_polar(&1.0, &(2.0 * PI * from as f64 / rate));
+	let w = num::Complex::from_polar(&1.0, &(-2.0 * PI * step / rate));
+
+	compute_in(input,
+		num::Complex::new(a.re as Precision, a.im as Precision),
+		num::Complex::new(w.re as Precision, w.im as Precision),
+		output);
+}
+
+/// Returns the frequencies of the points computed by `zoom`.
+pub fn frequencies(from: Precision, to: Precision, points: usize) -> Vec<Precision> {
+	let step = (to - from) / points as Precision;
+
+	(0 .. points).map(|k| from + k as Precision * step).collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use std::f64::consts::PI;
+	use num::Complex;
+	use {Precision, forward};
+
+	// a damped sinusoid, a pole inside the unit circle the spiral passes near
+	fn damped(length: usize) -> Vec<Precision> {
+		(0 .. length).map(|n| (0.9 as Precision).powi(n as i32) * (0.3 * n as Precision).cos()).collect()
+	}
+
+	#[test]
+	fn dft() {
+		let input    = damped(45);
+		let expected = forward::<_, Complex<Precision>, _>(&*input);
+		let output   = super::compute::<_, Complex<Precision>, _>(&*input, 45,
+			Complex::new(1.0, 0.0), Complex::from_polar(&1.0, &(-2.0 * PI as Precision / 45.0)));
+
+		for (a, b) in output.iter().zip(expected.iter()) {
+			assert!((a - b).norm() < 1e-3);
+		}
+	}
+
+	#[test]
+	fn spiral() {
+		let input = damped(20);
+		let a     = Complex::from_polar(&0.9, &0.3);
+		let w     = Complex::from_polar(&1.02, &-0.1);
+
+		let output = super::compute::<_, Complex<Precision>, _>(&*input, 7, a, w);
+
+		for (k, value) in output.iter().enumerate() {
+			let z        = a * w.powf(-(k as Precision));
+			let expected = input.iter().enumerate().fold(Complex::new(0.0, 0.0), |sum, (n, &x)|
+				sum + z.powf(-(n as Precision)) * x);
+
+			assert!((value - expected).norm() / expected.norm() < 1e-3);
+		}
+	}
+
+	#[test]
+	fn zoom() {
+		let input = (0 .. 1000)
+			.map(|n| (2.0 * PI as Precision * 1001.3 * n as Precision / 8000.0).cos())
+			.collect::<Vec<_>>();
+
+		let output = super::zoom::<_, Complex<Precision>, _>(&*input, 990.0, 1010.0, 8000, 200);
+		let peak   = output.iter().enumerate()
+			.max_by(|a, b| a.1.norm().partial_cmp(&b.1.norm()).unwrap())
+			.unwrap().0;
+
+		assert!((super::frequencies(990.0, 1010.0, 200)[peak] - 1001.3).abs() < 0.1);
+	}
+}
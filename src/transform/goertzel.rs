@@ -0,0 +1,405 @@
+use std::f64::consts::PI;
+use num;
+use strided::{Strided, MutStrided};
+
+use {Precision, Sample, ComplexMut};
+
+/// The state of the Goertzel recurrence for a single frequency, samples can be
+/// pushed as they arrive.
+///
+/// The value is the DTFT of the pushed samples at the given frequency, so for
+/// `frequency = k * rate / N` and `N` samples it equals bin `k` of the DFT, but
+/// the frequency doesn't have to fall on a bin.
+#[derive(Clone, Debug)]
+pub struct Goertzel {
+	omega:       Precision,
+	coefficient: Precision,
+	length:      usize,
+	s1:          Precision,
+	s2:          Precision,
+}
+
+impl Goertzel {
+	/// Creates the state for the given frequency and sample rate.
+	pub fn new(frequency: Precision, rate: u32) -> Self {
+		let omega = 2.0 * PI as Precision * frequency / rate as Precision;
+
+		Goertzel {
+			omega,
+			coefficient: 2.0 * omega.cos(),
+			length:      0,
+			s1:          0.0,
+			s2:          0.0,
+		}
+	}
+
+	/// Pushes a single sample.
+	#[inline]
+	pub fn push<S: Sample>(&mut self, sample: &S) {
+		let s0 = sample.normalize() + self.coefficient * self.s1 - self.s2;
+
+		self.s2      = self.s1;
+		self.s1      = s0;
+		self.length += 1;
+	}
+
+	/// Pushes all the samples in the given input.
+	pub fn extend<S, I>(&mut self, input: I)
+		where S: Sample,
+		      I: Strided<Elem=S>
+	{
+		for sample in input.as_stride().iter() {
+			self.push(sample);
+		}
+	}
+
+	/// The number of samples pushed since the last reset.
+	#[inline(always)]
+	pub fn len(&self) -> usize {
+		self.length
+	}
+
+	/// Checks if no samples were pushed since the last reset.
+	#[inline(always)]
+	pub fn is_empty(&self) -> bool {
+		self.length == 0
+	}
+
+	/// Computes the squared magnitude of the current value, cheaper than
+	/// computing the value itself.
+	#[inline]
+	pub fn power(&self) -> Precision {
+		(self.s1 * self.s1 + self.s2 * self.s2 - self.coefficient * self.s1 * self.s2).max(0.0)
+	}
+
+	/// Computes the current value, with the phase referenced to the first
+	/// sample pushed.
+	pub fn value<C: ComplexMut>(&self) -> C {
+		let mut output = C::zero();
+
+		if self.length > 0 {
+			let value = num::Complex::new(self.s1 - self.omega.cos() * self.s2, self.omega.sin() * self.s2)
+				* num::Complex::from_polar(&1.0, &(-self.omega * (self.length - 1) as Precision));
+
+			output.set(&value);
+		}
+
+		output
+	}
+
+	/// Clears the state so a new block can be pushed.
+	#[inline]
+	pub fn reset(&mut self) {
+		self.length = 0;
+		self.s1     = 0.0;
+		self.s2     = 0.0;
+	}
+}
+
+/// Computes the DTFT of the given input at the given frequency.
+#[inline]
+pub fn compute<S, C, I>(input: I, frequency: Precision, rate: u32) -> C
+	where S: Sample,
+	      C: ComplexMut,
+	      I: Strided<Elem=S>
+{
+	let mut state = Goertzel::new(frequency, rate);
+	state.extend(input);
+	state.value()
+}
+
+/// Computes the DTFT of the given input at every given frequency into the
+/// given output.
+pub fn compute_in<S, C, I, O>(input: I, frequencies: &[Precision], rate: u32, mut output: O)
+	where S: Sample,
+	      C: ComplexMut,
+	      I: Strided<Elem=S>,
+	      O: MutStrided<Elem=C>
+{
+	let     input  = input.as_stride();
+	let mut output = output.as_stride_mut();
+
+	debug_assert_eq!(frequencies.len(), output.len());
+
+	for (&frequency, output) in frequencies.iter().zip(output.iter_mut()) {
+		*output = compute(input, frequency, rate);
+	}
+}
+
+/// Computes the squared magnitude of the DTFT of the given input at the given
+/// frequency.
+#[inline]
+pub fn power<S, I>(input: I, frequency: Precision, rate: u32) -> Precision
+	where S: Sample,
+	      I: Strided<Elem=S>
+{
+	let mut state = Goertzel::new(frequency, rate);
+	state.extend(input);
+	state.power()
+}
+
+/// The row frequencies of the DTMF keypad.
+pub const ROWS: [Precision; 4] = [697.0, 770.0, 852.0, 941.0];
+
+/// The column frequencies of the DTMF keypad.
+pub const COLUMNS: [Precision; 4] = [1209.0, 1336.0, 1477.0, 1633.0];
+
+/// The DTMF keypad.
+const KEYS: [[char; 4]; 4] = [
+	['1', '2', '3', 'A'],
+	['4', '5', '6', 'B'],
+	['7', '8', '9', 'C'],
+	['*', '0', '#', 'D'],
+];
+
+/// The minimum mean power of a block to be considered.
+const LEVEL: Precision = 1e-4;
+
+/// The minimum fraction of the block energy in the two tones.
+const PURITY: Precision = 0.5;
+
+/// The maximum power ratio of the row tone over the column tone, 8 dB.
+const TWIST: Precision = 6.31;
+
+/// The maximum power ratio of the column tone over the row tone, 4 dB.
+const REVERSE: Precision = 2.51;
+
+/// The minimum power ratio of a tone over the others in its group, 6 dB.
+const SEPARATION: Precision = 3.98;
+
+/// Streaming DTMF decoder.
+///
+/// The input is split in blocks analyzed with a Goertzel filter for each of
+/// the eight tones, a key is reported once it's been detected in two
+/// consecutive blocks and again only after a block without it.
+#[derive(Clone, Debug)]
+pub struct Dtmf {
+	size:     usize,
+	filters:  Vec<Goertzel>,
+	energy:   Precision,
+	previous: Option<char>,
+	reported: Option<char>,
+}
+
+impl Dtmf {
+	/// Creates a decoder for the given sample rate with the customary block of
+	/// 205 samples at 8 kHz.
+	pub fn new(rate: u32) -> Self {
+		Dtmf::with_size(rate, (rate as usize * 205).div_ceil(8000))
+	}
+
+	/// Creates a decoder for the given sample rate and block size.
+	pub fn with_size(rate: u32, size: usize) -> Self {
+		debug_assert!(size > 0);
+
+		Dtmf {
+			size,
+			filters:  ROWS.iter().chain(COLUMNS.iter()).map(|&f| Goertzel::new(f, rate)).collect(),
+			energy:   0.0,
+			previous: None,
+			reported: None,
+		}
+	}
+
+	/// The size of the analyzed blocks.
+	#[inline(always)]
+	pub fn size(&self) -> usize {
+		self.size
+	}
+
+	/// Detects the key in the given block of samples, if any.
+	pub fn detect<S, I>(rate: u32, input: I) -> Option<char>
+		where S: Sample,
+		      I: Strided<Elem=S>
+	{
+		let     input   = input.as_stride();
+		let mut decoder = Dtmf::with_size(rate, input.len());
+
+		for sample in input.iter() {
+			decoder.accumulate(sample);
+		}
+
+		decoder.classify()
+	}
+
+	/// Decodes the given samples and returns the keys completed within them,
+	/// the state is kept across calls.
+	pub fn decode<S, I>(&mut self, input: I) -> Vec<char>
+		where S: Sample,
+		      I: Strided<Elem=S>
+	{
+		let mut output = Vec::new();
+
+		for sample in input.as_stride().iter() {
+			self.accumulate(sample);
+
+			if self.filters[0].len() < self.size {
+				continue;
+			}
+
+			let key = self.classify();
+
+			match key {
+				Some(value) if key == self.previous && key != self.reported => {
+					output.push(value);
+					self.reported = key;
+				}
+
+				None =>
+					self.reported = None,
+
+				_ => (),
+			}
+
+			self.previous = key;
+			self.energy   = 0.0;
+
+			for filter in self.filters.iter_mut() {
+				filter.reset();
+			}
+		}
+
+		output
+	}
+
+	/// Clears the state of the decoder.
+	pub fn reset(&mut self) {
+		self.energy   = 0.0;
+		self.previous = None;
+		self.reported = None;
+
+		for filter in self.filters.iter_mut() {
+			filter.reset();
+		}
+	}
+
+	#[inline]
+	fn accumulate<S: Sample>(&mut self, sample: &S) {
+		let value = sample.normalize();
+
+		self.energy += value * value;
+
+		for filter in self.filters.iter_mut() {
+			filter.push(sample);
+		}
+	}
+
+	fn classify(&self) -> Option<char> {
+		let length = self.filters[0].len() as Precision;
+
+		if length == 0.0 || self.energy / length < LEVEL {
+			return None;
+		}
+
+		let power = self.filters.iter().map(|f| f.power()).collect::<Vec<_>>();
+		let best  = |group: &[Precision]| (0 .. group.len())
+			.max_by(|&a, &b| group[a].partial_cmp(&group[b]).unwrap()).unwrap();
+
+		let (rows, columns) = power.split_at(4);
+		let row             = best(rows);
+		let column          = best(columns);
+
+		// a sinusoid of amplitude A has power (A N / 2)^2 and energy A^2 N / 2
+		if 2.0 * (rows[row] + columns[column]) / (length * self.energy) < PURITY {
+			return None;
+		}
+
+		if rows[row] > columns[column] * TWIST || columns[column] > rows[row] * REVERSE {
+			return None;
+		}
+
+		let separated = |group: &[Precision], index: usize|
+			group.iter().enumerate().all(|(i, &p)| i == index || p * SEPARATION <= group[index]);
+
+		if !separated(rows, row) || !separated(columns, column) {
+			return None;
+		}
+
+		Some(KEYS[row][column])
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::f64::consts::PI;
+	use num::Complex;
+	use super::{Goertzel, Dtmf, ROWS, COLUMNS};
+	use {Precision, forward};
+
+	// every tone gets a different phase, so the values aren't purely real
+	fn tones(length: usize, rate: u32, frequencies: &[Precision]) -> Vec<Precision> {
+		(0 .. length).map(|n| frequencies.iter().enumerate().map(|(i, &frequency)|
+			(2.0 * PI as Precision * frequency * n as Precision / rate as Precision + i as Precision).cos()
+		).sum()).collect()
+	}
+
+	fn key(rate: u32, row: usize, column: usize, length: usize) -> Vec<Precision> {
+		(0 .. length).map(|n| {
+			let t = n as Precision / rate as Precision;
+
+			0.4 * (2.0 * PI as Precision * ROWS[row] * t).sin() +
+			0.4 * (2.0 * PI as Precision * COLUMNS[column] * t).sin()
+		}).collect()
+	}
+
+	#[test]
+	fn bin() {
+		// one tone on bin 8 and one between bins, so every bin has energy
+		let input    = tones(64, 1000, &[125.0, 230.0]);
+		let spectrum = forward::<_, Complex<Precision>, _>(&*input);
+
+		for (k, expected) in spectrum.iter().enumerate() {
+			let value: Complex<Precision> = super::compute(&*input, k as Precision * 1000.0 / 64.0, 1000);
+
+			assert!((value - expected).norm() < 1e-3);
+			assert!((super::power(&*input, k as Precision * 1000.0 / 64.0, 1000) - expected.norm_sqr()).abs() < 1e-2);
+		}
+	}
+
+	#[test]
+	fn fractional() {
+		let input    = tones(100, 100, &[12.345, 30.0]);
+		let omega    = 2.0 * PI as Precision * 12.345 / 100.0;
+		let expected = input.iter().enumerate().fold(Complex::new(0.0, 0.0), |sum, (n, &x)|
+			sum + Complex::from_polar(&x, &(-omega * n as Precision)));
+
+		let value: Complex<Precision> = super::compute(&*input, 12.345, 100);
+
+		assert!((value - expected).norm() < 1e-3);
+	}
+
+	#[test]
+	fn streaming() {
+		let     input = tones(100, 100, &[7.5]);
+		let mut state = Goertzel::new(7.5, 100);
+
+		state.extend(&input[.. 40]);
+		state.extend(&input[40 ..]);
+
+		let streamed: Complex<Precision> = state.value();
+		let computed: Complex<Precision> = super::compute(&*input, 7.5, 100);
+
+		assert_eq!(state.len(), 100);
+		assert!((streamed - computed).norm() < 1e-4);
+	}
+
+	#[test]
+	fn dtmf() {
+		assert_eq!(Dtmf::detect(8000, &*key(8000, 1, 1, 205)), Some('5'));
+		assert_eq!(Dtmf::detect(8000, &*vec![0.0 as Precision; 205]), None);
+
+		let mut input = Vec::new();
+
+		for &(row, column) in &[(0, 0), (1, 1), (2, 2), (3, 2), (3, 2)] {
+			input.extend(key(8000, row, column, 1000));
+			input.extend(vec![0.0; 600]);
+		}
+
+		let mut decoder = Dtmf::new(8000);
+		let     keys    = decoder.decode(&input[.. 3000]).into_iter()
+			.chain(decoder.decode(&input[3000 ..]))
+			.collect::<String>();
+
+		assert_eq!(keys, "159##");
+	}
+}
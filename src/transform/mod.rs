@@ -6,3 +6,12 @@ pub mod bluestein;
 
 /// The naive algorithm.
 pub mod discrete;
+
+/// The Goertzel algorithm.
+pub mod goertzel;
+
+/// The sliding DFT.
+pub mod sliding;
+
+/// The chirp Z-transform.
+pub mod czt;
@@ -0,0 +1,297 @@
+use std::f64::consts::PI;
+use num::{self, Zero};
+use strided::{Strided, MutStrided};
+
+use {Precision, Sample, ComplexMut, forward};
+use window::{self, Function, Symmetry};
+
+/// The relative magnitude below which window spectrum terms are dropped.
+const EPSILON: Precision = 1e-5;
+
+/// The update rule of the sliding DFT.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Variant {
+	/// The classic recursive update, every bin is rotated by its twiddle factor
+	/// at every sample, so rounding errors accumulate over time.
+	Standard,
+
+	/// The modulated sliding DFT by Duda, the input is modulated instead of the
+	/// bins, so the twiddle factors come from a table and the errors don't
+	/// accumulate.
+	Modulated,
+}
+
+/// A sliding DFT over the last `size` samples, the bins are updated at every
+/// pushed sample.
+///
+/// Every update costs O(1) per tracked bin, so O(N) for all the bins or O(k)
+/// for a subset of `k` bins. Before `size` samples are pushed the missing
+/// samples are zero.
+#[derive(Clone, Debug)]
+pub struct Sliding {
+	variant:  Variant,
+	size:     usize,
+	bins:     Vec<usize>,
+	tracked:  Vec<usize>,
+	kernel:   Vec<(usize, num::Complex<Precision>)>,
+	twiddles: Vec<num::Complex<Precision>>,
+	states:   Vec<num::Complex<Precision>>,
+	history:  Vec<Precision>,
+	position: usize,
+}
+
+impl Sliding {
+	/// Creates a sliding DFT of the given size maintaining all the bins.
+	pub fn new(variant: Variant, size: usize) -> Self {
+		Sliding::with_bins(variant, size, &(0 .. size).collect::<Vec<_>>())
+	}
+
+	/// Creates a sliding DFT of the given size maintaining only the given bins.
+	pub fn with_bins(variant: Variant, size: usize, bins: &[usize]) -> Self {
+		debug_assert!(size > 0);
+		debug_assert!(bins.iter().all(|&bin| bin < size));
+
+		let twiddles = (0 .. size)
+			.map(|n| num::Complex::from_polar(&1.0, &(-2.0 * PI as Precision * n as Precision / size as Precision)))
+			.collect();
+
+		let mut sliding = Sliding {
+			variant,
+			size,
+			bins:     bins.to_vec(),
+			tracked:  Vec::new(),
+			kernel:   vec![(0, num::Complex::new(1.0, 0.0))],
+			twiddles,
+			states:   vec![num::Complex::zero(); size],
+			history:  vec![0.0; size],
+			position: 0,
+		};
+
+		sliding.track();
+		sliding
+	}
+
+	/// Applies the given window in the frequency domain, every output bin is
+	/// computed from its neighbours.
+	///
+	/// The periodic window is decomposed into its spectrum, raised-cosine
+	/// windows like `Hann`, `Hamming`, `Blackman` and `Nuttall` only have a few
+	/// terms, so they only need a few more bins to be tracked.
+	pub fn windowed<F: Function + 'static>(mut self) -> Self {
+		let window   = window::generate::<F, Precision, _>(.., Symmetry::Periodic, self.size);
+		let spectrum = forward::<_, num::Complex<Precision>, _>(&**window);
+		let largest  = spectrum.iter().fold(0.0, |max: Precision, c| max.max(c.norm()));
+
+		self.kernel = spectrum.iter().enumerate()
+			.filter(|&(_, c)| c.norm() > largest * EPSILON)
+			.map(|(offset, c)| (offset, c / self.size as Precision))
+			.collect();
+
+		self.track();
+		self.reset();
+		self
+	}
+
+	/// The number of samples the transform covers.
+	#[inline(always)]
+	pub fn size(&self) -> usize {
+		self.size
+	}
+
+	/// The bins the transform maintains.
+	#[inline(always)]
+	pub fn bins(&self) -> &[usize] {
+		&self.bins
+	}
+
+	/// Pushes a single sample.
+	pub fn push<S: Sample>(&mut self, sample: &S) {
+		let sample   = sample.normalize();
+		let position = self.position;
+		let delta    = sample - self.history[position];
+
+		self.history[position] = sample;
+		self.position          = (position + 1) % self.size;
+
+		match self.variant {
+			Variant::Standard =>
+				for &bin in &self.tracked {
+					let twiddle = self.twiddles[(self.size - bin) % self.size];
+					let state   = &mut self.states[bin];
+
+					*state = (*state + delta) * twiddle;
+				},
+
+			Variant::Modulated =>
+				for &bin in &self.tracked {
+					self.states[bin] = self.states[bin] + self.twiddles[bin * position % self.size] * delta;
+				},
+		}
+	}
+
+	/// Pushes all the samples in the given input.
+	pub fn extend<S, I>(&mut self, input: I)
+		where S: Sample,
+		      I: Strided<Elem=S>
+	{
+		for sample in input.as_stride().iter() {
+			self.push(sample);
+		}
+	}
+
+	/// Computes the current value of the given bin, which must be maintained.
+	pub fn value<C: ComplexMut>(&self, bin: usize) -> C {
+		debug_assert!(self.bins.contains(&bin));
+
+		let mut output = C::zero();
+		output.set(&self.kernel.iter().fold(num::Complex::<Precision>::zero(), |sum, &(offset, c)|
+			sum + self.state((bin + self.size - offset) % self.size) * c));
+
+		output
+	}
+
+	/// Computes the current values of the maintained bins.
+	#[inline(always)]
+	pub fn values<C: ComplexMut>(&self) -> Vec<C> {
+		let mut output = vec![C::zero(); self.bins.len()];
+		self.values_in(&mut *output);
+
+		output
+	}
+
+	/// Computes the current values of the maintained bins into the given output.
+	pub fn values_in<C, O>(&self, mut output: O)
+		where C: ComplexMut,
+		      O: MutStrided<Elem=C>
+	{
+		let mut output = output.as_stride_mut();
+
+		debug_assert_eq!(output.len(), self.bins.len());
+
+		for (&bin, output) in self.bins.iter().zip(output.iter_mut()) {
+			*output = self.value(bin);
+		}
+	}
+
+	/// Clears the samples and the bins.
+	pub fn reset(&mut self) {
+		self.position = 0;
+
+		for value in self.history.iter_mut() {
+			*value = 0.0;
+		}
+
+		for value in self.states.iter_mut() {
+			*value = num::Complex::zero();
+		}
+	}
+
+	/// The unwindowed DFT of the current samples at the given bin.
+	#[inline]
+	fn state(&self, bin: usize) -> num::Complex<Precision> {
+		match self.variant {
+			Variant::Standard =>
+				self.states[bin],
+
+			// the modulation is referenced to the absolute sample position
+			Variant::Modulated =>
+				self.states[bin] * self.twiddles[(self.size - bin * self.position % self.size) % self.size],
+		}
+	}
+
+	/// Collects the bins needed by the maintained bins through the window.
+	fn track(&mut self) {
+		let mut tracked = vec![false; self.size];
+
+		for &bin in &self.bins {
+			for &(offset, _) in &self.kernel {
+				tracked[(bin + self.size - offset) % self.size] = true;
+			}
+		}
+
+		self.tracked = (0 .. self.size).filter(|&bin| tracked[bin]).collect();
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::f64::consts::PI;
+	use num::Complex;
+	use super::{Sliding, Variant};
+	use window::{self, Hann, Blackman, Symmetry};
+	use {Precision, forward};
+
+	// a linear chirp from DC to Nyquist, so every bin sees energy as it slides
+	fn chirp(length: usize) -> Vec<Precision> {
+		(0 .. length).map(|n| {
+			let n = n as f64;
+
+			(PI * n * n / (2.0 * length as f64)).sin() as Precision
+		}).collect()
+	}
+
+	fn assert_close(a: &[Complex<Precision>], b: &[Complex<Precision>], epsilon: Precision) {
+		for (a, b) in a.iter().zip(b.iter()) {
+			assert!((a - b).norm() < epsilon, "{} != {}", a, b);
+		}
+	}
+
+	#[test]
+	fn standard() {
+		let     input   = chirp(100);
+		let mut sliding = Sliding::new(Variant::Standard, 32);
+
+		sliding.extend(&*input);
+
+		assert_close(&sliding.values::<Complex<Precision>>(), &forward::<_, Complex<Precision>, _>(&input[68 ..]), 1e-3);
+	}
+
+	#[test]
+	fn modulated() {
+		let     input   = chirp(10_000);
+		let mut sliding = Sliding::new(Variant::Modulated, 32);
+
+		for (n, sample) in input.iter().enumerate() {
+			sliding.push(sample);
+
+			if n == 20 {
+				let mut padded = vec![0.0; 11];
+				padded.extend_from_slice(&input[.. 21]);
+
+				assert_close(&sliding.values::<Complex<Precision>>(), &forward::<_, Complex<Precision>, _>(&*padded), 1e-3);
+			}
+		}
+
+		assert_close(&sliding.values::<Complex<Precision>>(), &forward::<_, Complex<Precision>, _>(&input[10_000 - 32 ..]), 1e-2);
+	}
+
+	#[test]
+	fn bins() {
+		let     input   = chirp(50);
+		let mut sliding = Sliding::with_bins(Variant::Modulated, 16, &[3, 5]);
+
+		sliding.extend(&*input);
+
+		let expected = forward::<_, Complex<Precision>, _>(&input[34 ..]);
+		assert_close(&sliding.values::<Complex<Precision>>(), &[expected[3], expected[5]], 1e-3);
+	}
+
+	#[test]
+	fn windowed() {
+		let input = chirp(70);
+
+		let     hann    = window::generate::<Hann, Precision, _>(.., Symmetry::Periodic, 32);
+		let mut sliding = Sliding::with_bins(Variant::Modulated, 32, &[0, 4, 31]).windowed::<Hann>();
+		sliding.extend(&*input);
+
+		let expected = forward::<_, Complex<Precision>, _>(&*hann.apply::<Precision, _, _>(&input[38 ..]));
+		assert_close(&sliding.values::<Complex<Precision>>(), &[expected[0], expected[4], expected[31]], 1e-3);
+
+		let     blackman = window::generate::<Blackman, Precision, _>(.., Symmetry::Periodic, 32);
+		let mut sliding  = Sliding::new(Variant::Standard, 32).windowed::<Blackman>();
+		sliding.extend(&*input);
+
+		let expected = forward::<_, Complex<Precision>, _>(&*blackman.apply::<Precision, _, _>(&input[38 ..]));
+		assert_close(&sliding.values::<Complex<Precision>>(), &expected, 1e-3);
+	}
+}
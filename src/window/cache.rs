@@ -0,0 +1,164 @@
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::ops;
+use std::sync::Arc;
+
+use super::range::{Range, Shaped};
+use super::symmetry::Symmetry;
+use super::window::Window;
+use super::chebyshev::Chebyshev;
+use super::kaiser::Kaiser;
+use super::{Function, generate};
+use SampleMut;
+
+#[derive(PartialEq, Eq, Hash, Clone, Debug)]
+struct Key {
+	function:  TypeId,
+	parameter: Option<u64>,
+	size:      usize,
+	symmetry:  Symmetry,
+	range:     Shaped<ops::Range<u32>>,
+}
+
+/// Cache of generated windows keyed by function, size, symmetry and range.
+///
+/// Windows are generated on first request and shared from then on.
+#[derive(Clone, Debug)]
+pub struct Cache<S: SampleMut> {
+	windows: HashMap<Key, Arc<Window<S>>>,
+}
+
+impl<S: SampleMut> Default for Cache<S> {
+	fn default() -> Self {
+		Cache::new()
+	}
+}
+
+impl<S: SampleMut> Cache<S> {
+	/// Creates an empty cache.
+	pub fn new() -> Self {
+		Cache {
+			windows: HashMap::new(),
+		}
+	}
+
+	/// Gets the window for the given function within the given range for the
+	/// given window size, generating it if it's not cached.
+	pub fn get<F, R>(&mut self, range: R, symmetry: Symmetry, size: usize) -> Arc<Window<S>>
+		where F: Function + 'static,
+		      R: Range
+	{
+		let key = Key {
+			function:  TypeId::of::<F>(),
+			parameter: None,
+			size,
+			symmetry,
+			range:     Shaped::resolve(&range, size),
+		};
+
+		self.windows.entry(key).or_insert_with(||
+			Arc::new(generate::<F, S, R>(range, symmetry, size))).clone()
+	}
+
+	/// Gets the given Dolph-Chebyshev window within the given range for the
+	/// given window size, generating it if it's not cached.
+	pub fn chebyshev<R>(&mut self, window: &Chebyshev, range: R, symmetry: Symmetry, size: usize) -> Arc<Window<S>>
+		where R: Range
+	{
+		let key = Key {
+			function:  TypeId::of::<Chebyshev>(),
+			parameter: Some(window.key()),
+			size,
+			symmetry,
+			range:     Shaped::resolve(&range, size),
+		};
+
+		self.windows.entry(key).or_insert_with(||
+			Arc::new(window.generate(range, symmetry, size))).clone()
+	}
+
+	/// Gets the given Kaiser window within the given range for the given window
+	/// size, generating it if it's not cached.
+	pub fn kaiser<R>(&mut self, window: &Kaiser, range: R, symmetry: Symmetry, size: usize) -> Arc<Window<S>>
+		where R: Range
+	{
+		let key = Key {
+			function:  TypeId::of::<Kaiser>(),
+			parameter: Some(window.key()),
+			size,
+			symmetry,
+			range:     Shaped::resolve(&range, size),
+		};
+
+		self.windows.entry(key).or_insert_with(||
+			Arc::new(window.generate(range, symmetry, size))).clone()
+	}
+
+	/// The number of cached windows.
+	#[inline(always)]
+	pub fn len(&self) -> usize {
+		self.windows.len()
+	}
+
+	/// Checks if there are no cached windows.
+	#[inline(always)]
+	pub fn is_empty(&self) -> bool {
+		self.windows.is_empty()
+	}
+
+	/// Drops all the cached windows, the ones already handed out stay valid.
+	pub fn clear(&mut self) {
+		self.windows.clear();
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::sync::Arc;
+	use window::{Range, Symmetry, Hann, Hamming, Chebyshev};
+	use super::Cache;
+
+	#[test]
+	fn get() {
+		let mut cache = Cache::<f32>::new();
+
+		let a = cache.get::<Hann, _>(.., Symmetry::Periodic, 1024);
+		let b = cache.get::<Hann, _>(0 .. 1024, Symmetry::Periodic, 1024);
+		let c = cache.get::<Hann, _>(.., Symmetry::Symmetric, 1024);
+		let d = cache.get::<Hamming, _>(.., Symmetry::Periodic, 1024);
+		let e = cache.get::<Hann, _>((..).pass(), Symmetry::Periodic, 1024);
+
+		assert!(Arc::ptr_eq(&a, &b));
+		assert!(!Arc::ptr_eq(&a, &c));
+		assert!(!Arc::ptr_eq(&a, &d));
+		assert!(!Arc::ptr_eq(&a, &e));
+		assert_eq!(cache.len(), 4);
+
+		assert!(a.is::<Hann>());
+		assert!(d.is::<Hamming>());
+		assert_eq!(a.size(), 1024);
+		assert_eq!(a.symmetry(), Symmetry::Periodic);
+	}
+
+	#[test]
+	fn chebyshev() {
+		let mut cache = Cache::<f32>::new();
+
+		let a = cache.chebyshev(&Chebyshev::new(60.0), .., Symmetry::Symmetric, 64);
+		let b = cache.chebyshev(&Chebyshev::new(60.0), .., Symmetry::Symmetric, 64);
+		let c = cache.chebyshev(&Chebyshev::new(80.0), .., Symmetry::Symmetric, 64);
+
+		assert!(Arc::ptr_eq(&a, &b));
+		assert!(!Arc::ptr_eq(&a, &c));
+		assert!(a.is::<Chebyshev>());
+	}
+
+	#[test]
+	#[should_panic(expected = "window size mismatch")]
+	fn mismatch() {
+		let mut cache = Cache::<f32>::new();
+		let     data  = vec![0.0f32; 512];
+
+		cache.get::<Hann, _>(.., Symmetry::Periodic, 1024).apply::<f32, _, _>(&*data);
+	}
+}
@@ -0,0 +1,151 @@
+use std::any::TypeId;
+use std::f64::consts::PI;
+use num;
+
+use super::range::Range;
+use super::window::Window;
+use super::symmetry::Symmetry;
+use {Precision, SampleMut, inverse};
+
+/// https://en.wikipedia.org/wiki/Window_function#Dolph.E2.80.93Chebyshev_window
+///
+/// Unlike the other windows it cannot be computed point by point, the
+/// coefficients are computed in the frequency domain and brought back with an
+/// inverse transform, so it only provides cached windows.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct Chebyshev {
+	attenuation: Precision,
+}
+
+impl Chebyshev {
+	/// Creates a Dolph-Chebyshev window with the given sidelobe attenuation in
+	/// decibels.
+	pub fn new(attenuation: Precision) -> Self {
+		Chebyshev {
+			attenuation: attenuation.abs(),
+		}
+	}
+
+	/// The sidelobe attenuation in decibels.
+	#[inline(always)]
+	pub fn attenuation(&self) -> Precision {
+		self.attenuation
+	}
+
+	#[doc(hidden)]
+	#[inline(always)]
+	pub fn key(&self) -> u64 {
+		(self.attenuation as f64).to_bits()
+	}
+
+	/// Computes the coefficients for a window of the given size, normalized so
+	/// the peak is `1.0`.
+	pub fn compute(&self, size: usize) -> Vec<Precision> {
+		if size <= 1 {
+			return vec![1.0; size];
+		}
+
+		let order = (size - 1) as Precision;
+		let beta  = ((10.0 as Precision).powf(self.attenuation / 20.0).acosh() / order).cosh();
+
+		// the Chebyshev polynomial of the given order sampled on the unit circle,
+		// rotated by half a bin for even sizes so the result comes out real
+		let mut spectrum = Vec::with_capacity(size);
+
+		for k in 0 .. size {
+			let x = beta * (PI as Precision * k as Precision / size as Precision).cos();
+
+			let value = if x > 1.0 {
+				(order * x.acosh()).cosh()
+			}
+			else if x < -1.0 {
+				let sign = if size % 2 == 1 { 1.0 } else { -1.0 };
+
+				sign * (order * (-x).acosh()).cosh()
+			}
+			else {
+				(order * x.acos()).cos()
+			};
+
+			if size % 2 == 1 {
+				spectrum.push(num::Complex::new(value, 0.0));
+			}
+			else {
+				spectrum.push(num::Complex::from_polar(&value,
+					&(-PI as Precision * k as Precision / size as Precision)));
+			}
+		}
+
+		let time: Vec<num::Complex<Precision>> = inverse(&*spectrum);
+
+		// the result is centered on the first sample, unfold it
+		let     half   = size / 2;
+		let     skip   = if size % 2 == 1 { 0 } else { 1 };
+		let mut output = Vec::with_capacity(size);
+
+		output.extend(time[1 .. half + 1].iter().rev().map(|c| c.re));
+		output.extend(time[skip .. half + 1].iter().map(|c| c.re));
+
+		let max = output.iter().fold(0.0, |max: Precision, &v| max.max(v));
+
+		for value in output.iter_mut() {
+			*value /= max;
+		}
+
+		output
+	}
+
+	/// Generates the window within the given range for the given window size.
+	pub fn generate<S, R>(&self, range: R, symmetry: Symmetry, size: usize) -> Window<S>
+		where S: SampleMut,
+		      R: Range
+	{
+		super::parametric(TypeId::of::<Chebyshev>(), self.key(), range, symmetry, size, |width| self.compute(width))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::Chebyshev;
+	use window::{Range, Symmetry};
+
+	macro_rules! assert_approx_eq {
+		($a:expr, $b:expr) => (
+			assert_eq!($a.len(), $b.len());
+
+			for (a, b) in $a.iter().zip($b.iter()) {
+				assert_eq!(format!("{:.3}", a), format!("{:.3}", b));
+			}
+		)
+	}
+
+	#[test]
+	fn odd() {
+		assert_approx_eq!(Chebyshev::new(100.0).compute(5),
+			[0.169, 0.669, 1.000, 0.669, 0.169]);
+	}
+
+	#[test]
+	fn even() {
+		assert_approx_eq!(Chebyshev::new(60.0).compute(6),
+			[0.133, 0.548, 1.000, 1.000, 0.548, 0.133]);
+	}
+
+	#[test]
+	fn generate() {
+		let window = Chebyshev::new(100.0).generate::<f64, _>(.., Symmetry::Symmetric, 5);
+		assert_approx_eq!(window, [0.169, 0.669, 1.000, 0.669, 0.169]);
+	}
+
+	#[test]
+	fn periodic() {
+		let window = Chebyshev::new(60.0).generate::<f64, _>(.., Symmetry::Periodic, 5);
+		assert_approx_eq!(window, [0.133, 0.548, 1.000, 1.000, 0.548]);
+	}
+
+	#[test]
+	fn fade_in() {
+		let window = Chebyshev::new(60.0).generate::<f64, _>((1 .. 4).fade_in(), Symmetry::Symmetric, 5);
+		assert_approx_eq!(window, [0.000, 0.133, 0.548, 1.000, 1.000]);
+	}
+}
@@ -0,0 +1,109 @@
+use std::any::TypeId;
+
+use super::range::Range;
+use super::window::Window;
+use super::symmetry::Symmetry;
+use {Precision, SampleMut};
+
+/// https://en.wikipedia.org/wiki/Kaiser_window
+///
+/// The shape depends on the `beta` parameter, so like `Chebyshev` it only
+/// provides cached windows instead of implementing `Function`.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct Kaiser {
+	beta: Precision,
+}
+
+impl Kaiser {
+	/// Creates a Kaiser window with the given shape parameter, `0.0` is
+	/// rectangular and larger values trade main lobe width for sidelobe
+	/// attenuation.
+	pub fn new(beta: Precision) -> Self {
+		Kaiser {
+			beta: beta.abs(),
+		}
+	}
+
+	/// The shape parameter.
+	#[inline(always)]
+	pub fn beta(&self) -> Precision {
+		self.beta
+	}
+
+	#[doc(hidden)]
+	#[inline(always)]
+	pub fn key(&self) -> u64 {
+		(self.beta as f64).to_bits()
+	}
+
+	/// Computes the coefficients for a window of the given size.
+	pub fn compute(&self, size: usize) -> Vec<Precision> {
+		if size <= 1 {
+			return vec![1.0; size];
+		}
+
+		let order = (size - 1) as Precision;
+		let scale = bessel(self.beta);
+
+		(0 .. size).map(|n| {
+			let x = 2.0 * n as Precision / order - 1.0;
+
+			bessel(self.beta * (1.0 - x * x).max(0.0).sqrt()) / scale
+		}).collect()
+	}
+
+	/// Generates the window within the given range for the given window size.
+	pub fn generate<S, R>(&self, range: R, symmetry: Symmetry, size: usize) -> Window<S>
+		where S: SampleMut,
+		      R: Range
+	{
+		super::parametric(TypeId::of::<Kaiser>(), self.key(), range, symmetry, size, |width| self.compute(width))
+	}
+}
+
+/// The zeroth order modified Bessel function of the first kind.
+fn bessel(x: Precision) -> Precision {
+	let     half  = (x / 2.0) * (x / 2.0);
+	let mut sum   = 1.0;
+	let mut term  = 1.0;
+	let mut k     = 1.0;
+
+	while term > sum * Precision::EPSILON {
+		term *= half / (k * k);
+		sum  += term;
+		k    += 1.0;
+	}
+
+	sum
+}
+
+#[cfg(test)]
+mod tests {
+	use super::Kaiser;
+	use window::Symmetry;
+
+	macro_rules! assert_approx_eq {
+		($a:expr, $b:expr) => (
+			assert_eq!($a.len(), $b.len());
+
+			for (a, b) in $a.iter().zip($b.iter()) {
+				assert_eq!(format!("{:.3}", a), format!("{:.3}", b));
+			}
+		)
+	}
+
+	#[test]
+	fn compute() {
+		assert_approx_eq!(Kaiser::new(5.0).compute(5),
+			[0.037, 0.553, 1.000, 0.553, 0.037]);
+
+		assert_approx_eq!(Kaiser::new(0.0).compute(4),
+			[1.000, 1.000, 1.000, 1.000]);
+	}
+
+	#[test]
+	fn periodic() {
+		let window = Kaiser::new(5.0).generate::<f64, _>(.., Symmetry::Periodic, 4);
+		assert_approx_eq!(window, [0.037, 0.553, 1.000, 0.553]);
+	}
+}
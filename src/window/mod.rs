@@ -1,9 +1,15 @@
 mod range;
-pub use self::range::Range;
+pub use self::range::{Range, Shaped, Outside, Shape, Location};
+
+mod symmetry;
+pub use self::symmetry::Symmetry;
 
 mod window;
 pub use self::window::Window;
 
+mod cache;
+pub use self::cache::Cache;
+
 mod rectangular;
 pub use self::rectangular::Rectangular;
 
@@ -31,7 +37,13 @@ pub use self::blackman::Blackman;
 mod nuttall;
 pub use self::nuttall::Nuttall;
 
-use num::Zero;
+mod chebyshev;
+pub use self::chebyshev::Chebyshev;
+
+mod kaiser;
+pub use self::kaiser::Kaiser;
+
+use std::any::TypeId;
 
 use {Precision, Sample, SampleMut};
 use strided::{Strided, MutStrided};
@@ -44,20 +56,42 @@ pub trait Function {
 
 /// Computes the window function for the given index and window size.
 #[inline(always)]
-pub fn compute<F, S>(index: usize, width: usize) -> S
+pub fn compute<F, S>(index: usize, symmetry: Symmetry, width: usize) -> S
 	where F: Function,
 	      S: SampleMut
 {
 	let mut result = S::zero();
-	result.set_normalized(F::compute(index as Precision, width as Precision));
+	result.set_normalized(F::compute(index as Precision, symmetry.width(width) as Precision));
 
 	result
 }
 
+/// Computes the gain of the window function at the given index in a buffer of
+/// the given size.
+#[inline]
+pub fn gain<F, R>(range: &R, symmetry: Symmetry, index: usize, size: usize) -> Precision
+	where F: Function,
+	      R: Range
+{
+	match range.locate(index, size) {
+		Location::Window(n, width) =>
+			F::compute(n as Precision, symmetry.width(width) as Precision),
+
+		Location::Zero =>
+			0.0,
+
+		Location::Pass =>
+			1.0,
+	}
+}
+
 /// Applies the given window function within the given range on the given input
 /// returning a new vector with the applied window function.
+///
+/// The `symmetry` picks between the symmetric form, for filter design, and the
+/// periodic form, for spectral analysis.
 #[inline(always)]
-pub fn apply<F, SO, SI, I, R>(range: R, input: I) -> Vec<SO>
+pub fn apply<F, SO, SI, I, R>(range: R, symmetry: Symmetry, input: I) -> Vec<SO>
 	where F:  Function,
 	      SO: SampleMut,
 	      SI: Sample,
@@ -71,14 +105,14 @@ pub fn apply<F, SO, SI, I, R>(range: R, input: I) -> Vec<SO>
 	// Check the range are valid for the window.
 	debug_assert!(range.is_valid(length));
 
-	apply_in::<F, SO, SI, _, _, R>(range, input, &mut *output);
+	apply_in::<F, SO, SI, _, _, R>(range, symmetry, input, &mut *output);
 
 	output
 }
 
 /// Applies the given window function within the given range on the given input
 /// putting the result into the given output.
-pub fn apply_in<F, SO, SI, I, O, R>(range: R, input: I, mut output: O)
+pub fn apply_in<F, SO, SI, I, O, R>(range: R, symmetry: Symmetry, input: I, mut output: O)
 	where F:  Function,
 	      SO: SampleMut,
 	      SI: Sample,
@@ -97,17 +131,13 @@ pub fn apply_in<F, SO, SI, I, O, R>(range: R, input: I, mut output: O)
 	debug_assert!(range.is_valid(length));
 
 	for (index, (input, output)) in input.iter().zip(output.iter_mut()).enumerate() {
-		if index >= range.start().unwrap_or(0) as usize &&
-		   index <= range.end().unwrap_or(length as u32) as usize
-		{
-			output.set_normalized(input.normalize()
-				* F::compute(index as Precision, range.width(length) as Precision));
-		}
+		output.set_normalized(input.normalize()
+			* gain::<F, R>(&range, symmetry, index, length));
 	}
 }
 
 /// Applies the given window function within the given range in-place.
-pub fn apply_on<F, S, IO, R>(range: R, mut data: IO)
+pub fn apply_on<F, S, IO, R>(range: R, symmetry: Symmetry, mut data: IO)
 	where F:  Function,
 	      S:  SampleMut,
 	      IO: MutStrided<Elem=S>,
@@ -120,37 +150,100 @@ pub fn apply_on<F, S, IO, R>(range: R, mut data: IO)
 	debug_assert!(range.is_valid(length));
 
 	for (index, datum) in data.iter_mut().enumerate() {
-		if index >= range.start().unwrap_or(0) as usize &&
-		   index <= range.end().unwrap_or(length as u32) as usize
-		{
-			let value = datum.normalize();
-
-			datum.set_normalized(value
-				* F::compute(index as Precision, range.width(length) as Precision));
+		// avoid a lossy round-trip through the normalized value
+		if let Location::Pass = range.locate(index, length) {
+			continue;
 		}
+
+		let value = datum.normalize();
+
+		datum.set_normalized(value
+			* gain::<F, R>(&range, symmetry, index, length));
 	}
 }
 
 /// Generates a window function within the given range for the given window
 /// size.
-pub fn generate<F, S, R>(range: R, size: usize) -> Window<S>
-	where F: Function,
+pub fn generate<F, S, R>(range: R, symmetry: Symmetry, size: usize) -> Window<S>
+	where F: Function + 'static,
 	      S: SampleMut,
 	      R: Range
 {
-	let mut output = Window::new(&range, size);
+	let mut output = Window::new(TypeId::of::<F>(), None, &range, symmetry, size);
 
 	// Check the range are valid for the window.
 	debug_assert!(range.is_valid(size));
 
 	for (index, output) in output.iter_mut().enumerate() {
-		if index >= range.start().unwrap_or(0) as usize &&
-		   index <= range.end().unwrap_or(size as u32) as usize
-		{
-			SampleMut::set_normalized(output,
-				F::compute(index as Precision, range.width(size) as Precision));
-		}
+		SampleMut::set_normalized(output,
+			gain::<F, R>(&range, symmetry, index, size));
 	}
 
 	output
 }
+
+/// Generates a parametric window within the given range for the given window
+/// size, the coefficients are computed for the width the range needs.
+fn parametric<S, R, C>(function: TypeId, parameter: u64, range: R, symmetry: Symmetry, size: usize, compute: C) -> Window<S>
+	where S: SampleMut,
+	      R: Range,
+	      C: FnOnce(usize) -> Vec<Precision>
+{
+	let mut output: Window<S> = Window::new(function, Some(parameter), &range, symmetry, size);
+
+	// Check the range are valid for the window.
+	debug_assert!(range.is_valid(size));
+
+	// fades use half of a window twice as wide as the range
+	let width = match range.shape() {
+		Shape::Full => range.width(size) as usize,
+		_           => range.width(size) as usize * 2,
+	};
+
+	let coefficients = compute(symmetry.width(width));
+
+	for (index, output) in output.iter_mut().enumerate() {
+		output.set_normalized(match range.locate(index, size) {
+			Location::Window(n, _) => coefficients[n],
+			Location::Zero         => 0.0,
+			Location::Pass         => 1.0,
+		});
+	}
+
+	output
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{Range, Symmetry, Hann};
+
+	macro_rules! assert_approx_eq {
+		($a:expr, $b:expr) => (
+			assert_eq!($a.len(), $b.len());
+
+			for (a, b) in $a.iter().zip($b.iter()) {
+				assert_eq!(format!("{:.2}", a), format!("{:.2}", b));
+			}
+		)
+	}
+
+	#[test]
+	fn generate() {
+		assert_approx_eq!(super::generate::<Hann, f64, _>(2 .. 7, Symmetry::Symmetric, 9),
+			[0.00, 0.00, 0.00, 0.50, 1.00, 0.50, 0.00, 0.00, 0.00]);
+
+		assert_approx_eq!(super::generate::<Hann, f64, _>((2 .. 7).pass(), Symmetry::Symmetric, 9),
+			[1.00, 1.00, 0.00, 0.50, 1.00, 0.50, 0.00, 1.00, 1.00]);
+
+		assert_approx_eq!(super::generate::<Hann, f64, _>(.., Symmetry::Periodic, 4),
+			[0.00, 0.50, 1.00, 0.50]);
+	}
+
+	#[test]
+	fn apply_on() {
+		let mut data = vec![1.0f64; 6];
+		super::apply_on::<Hann, _, _, _>((3 ..).fade_out(), Symmetry::Symmetric, &mut *data);
+
+		assert_approx_eq!(data, [1.00, 1.00, 1.00, 0.90, 0.35, 0.00]);
+	}
+}
@@ -1,6 +1,48 @@
 use std::ops;
 
+/// What happens to the samples outside of the range.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum Outside {
+	/// The samples are zeroed.
+	Zero,
+
+	/// The samples are passed through untouched.
+	Pass,
+}
+
+/// Which part of the window is placed within the range.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum Shape {
+	/// The whole window is stretched over the range.
+	Full,
+
+	/// The rising half of the window is stretched over the range, the samples
+	/// after the range are passed through.
+	FadeIn,
+
+	/// The falling half of the window is stretched over the range, the samples
+	/// before the range are passed through.
+	FadeOut,
+}
+
+/// Where an index in the buffer falls relative to the window.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Location {
+	/// The index is at the given position in a window of the given width.
+	Window(usize, usize),
+
+	/// The sample has to be zeroed.
+	Zero,
+
+	/// The sample has to be passed through.
+	Pass,
+}
+
 /// Trait for a window range argument.
+///
+/// The window is shifted to the start of the range and stretched over its
+/// width, what happens to the samples outside of it depends on `outside` and
+/// `shape`.
 pub trait Range: Clone {
 	/// The start of the range, if any.
 	fn start(&self) -> Option<u32> {
@@ -12,6 +54,16 @@ pub trait Range: Clone {
 		None
 	}
 
+	/// What happens to the samples outside of the range.
+	fn outside(&self) -> Outside {
+		Outside::Zero
+	}
+
+	/// Which part of the window is placed within the range.
+	fn shape(&self) -> Shape {
+		Shape::Full
+	}
+
 	/// The total width of the range.
 	fn width(&self, size: usize) -> u32 {
 		self.end().unwrap_or(size as u32) - self.start().unwrap_or(0)
@@ -19,11 +71,112 @@ pub trait Range: Clone {
 
 	/// Checks if the range is valid for the given size.
 	fn is_valid(&self, size: usize) -> bool {
-		if let Some(end) = self.end() {
-			return end as usize <= size;
+		let start = self.start().unwrap_or(0) as usize;
+		let end   = self.end().unwrap_or(size as u32) as usize;
+
+		start <= end && end <= size
+	}
+
+	/// Locates the given index in a buffer of the given size.
+	fn locate(&self, index: usize, size: usize) -> Location {
+		let start = self.start().unwrap_or(0) as usize;
+		let end   = self.end().unwrap_or(size as u32) as usize;
+		let width = end - start;
+
+		let outside = match self.outside() {
+			Outside::Zero => Location::Zero,
+			Outside::Pass => Location::Pass,
+		};
+
+		match self.shape() {
+			Shape::Full if index < start || index >= end =>
+				outside,
+
+			Shape::Full =>
+				Location::Window(index - start, width),
+
+			Shape::FadeIn if index < start =>
+				outside,
+
+			Shape::FadeIn if index >= end =>
+				Location::Pass,
+
+			Shape::FadeIn =>
+				Location::Window(index - start, width * 2),
+
+			Shape::FadeOut if index < start =>
+				Location::Pass,
+
+			Shape::FadeOut if index >= end =>
+				outside,
+
+			Shape::FadeOut =>
+				Location::Window(index - start + width, width * 2),
 		}
+	}
+
+	/// Passes through the samples outside of the range.
+	fn pass(self) -> Shaped<Self> {
+		Shaped { outside: Outside::Pass, shape: self.shape(), range: self }
+	}
+
+	/// Zeroes the samples outside of the range.
+	fn zero(self) -> Shaped<Self> {
+		Shaped { outside: Outside::Zero, shape: self.shape(), range: self }
+	}
+
+	/// Places the rising half of the window within the range.
+	fn fade_in(self) -> Shaped<Self> {
+		Shaped { outside: self.outside(), shape: Shape::FadeIn, range: self }
+	}
+
+	/// Places the falling half of the window within the range.
+	fn fade_out(self) -> Shaped<Self> {
+		Shaped { outside: self.outside(), shape: Shape::FadeOut, range: self }
+	}
+}
+
+/// A range with a specific outside handling and shape.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub struct Shaped<R: Range> {
+	range:   R,
+	outside: Outside,
+	shape:   Shape,
+}
+
+impl<R: Range> Shaped<R> {
+	/// Creates a range with the given outside handling and shape.
+	pub fn new(range: R, outside: Outside, shape: Shape) -> Self {
+		Shaped { range, outside, shape }
+	}
+}
 
-		true
+impl Shaped<ops::Range<u32>> {
+	/// Resolves the given range for the given size, so ranges covering the same
+	/// samples compare equal.
+	pub fn resolve<R: Range>(range: &R, size: usize) -> Self {
+		Shaped::new(ops::Range {
+			start: range.start().unwrap_or(0),
+			end:   range.end().unwrap_or(size as u32),
+		}, range.outside(), range.shape())
+	}
+}
+
+impl<R: Range> Range for Shaped<R> {
+	fn start(&self) -> Option<u32> {
+		self.range.start()
+	}
+
+	fn end(&self) -> Option<u32> {
+		self.range.end()
+	}
+
+	fn outside(&self) -> Outside {
+		self.outside
+	}
+
+	fn shape(&self) -> Shape {
+		self.shape
 	}
 }
 
@@ -50,3 +203,41 @@ impl Range for ops::RangeFrom<u32> {
 }
 
 impl Range for ops::RangeFull { }
+
+#[cfg(test)]
+mod tests {
+	use super::{Range, Location};
+
+	#[test]
+	fn full() {
+		let range = 100 .. 300;
+
+		assert_eq!(range.locate(0,   400), Location::Zero);
+		assert_eq!(range.locate(100, 400), Location::Window(0, 200));
+		assert_eq!(range.locate(299, 400), Location::Window(199, 200));
+		assert_eq!(range.locate(300, 400), Location::Zero);
+
+		let range = range.pass();
+
+		assert_eq!(range.locate(0,   400), Location::Pass);
+		assert_eq!(range.locate(300, 400), Location::Pass);
+	}
+
+	#[test]
+	fn fade_in() {
+		let range = (.. 100).fade_in();
+
+		assert_eq!(range.locate(0,   400), Location::Window(0, 200));
+		assert_eq!(range.locate(99,  400), Location::Window(99, 200));
+		assert_eq!(range.locate(100, 400), Location::Pass);
+	}
+
+	#[test]
+	fn fade_out() {
+		let range = (300 ..).fade_out();
+
+		assert_eq!(range.locate(0,   400), Location::Pass);
+		assert_eq!(range.locate(300, 400), Location::Window(100, 200));
+		assert_eq!(range.locate(399, 400), Location::Window(199, 200));
+	}
+}
@@ -0,0 +1,27 @@
+/// The symmetry of a generated window.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Default, Debug)]
+pub enum Symmetry {
+	/// The window is symmetric around its center, both ends have the same value.
+	///
+	/// This is the form used for filter design.
+	#[default]
+	Symmetric,
+
+	/// The window is one sample longer than requested and the last sample is
+	/// dropped, so it repeats seamlessly (DFT-even).
+	///
+	/// This is the form used for spectral analysis.
+	Periodic,
+}
+
+impl Symmetry {
+	/// The width the window function has to be computed on for the given
+	/// window width.
+	#[inline(always)]
+	pub fn width(&self, width: usize) -> usize {
+		match *self {
+			Symmetry::Symmetric => width,
+			Symmetry::Periodic  => width + 1,
+		}
+	}
+}
@@ -1,30 +1,62 @@
+use std::any::TypeId;
 use std::ops::{self, Deref, DerefMut};
-use num::Zero;
 use strided::{Strided, MutStrided};
 
-use super::range::Range;
+use super::range::{Range, Shaped, Location};
+use super::symmetry::Symmetry;
 use {Sample, SampleMut};
 
 /// Represents a cached window function on a range and window size.
+///
+/// It remembers the function, size, symmetry and range it was generated with.
 #[derive(PartialEq, Clone, Debug)]
 pub struct Window<S: SampleMut> {
-	range:  ops::Range<u32>,
-	buffer: Vec<S>,
+	function:  TypeId,
+	parameter: Option<u64>,
+	size:      usize,
+	symmetry:  Symmetry,
+	range:     Shaped<ops::Range<u32>>,
+	buffer:    Vec<S>,
 }
 
 impl<S0: SampleMut> Window<S0> {
 	#[doc(hidden)]
-	pub fn new<R: Range>(range: &R, size: usize) -> Self {
+	pub fn new<R: Range>(function: TypeId, parameter: Option<u64>, range: &R, symmetry: Symmetry, size: usize) -> Self {
 		Window {
-			range: ops::Range {
-				start: range.start().unwrap_or(0),
-				end:   range.end().unwrap_or(size as u32),
-			},
+			function,
+			parameter,
+			size,
+			symmetry,
 
+			range:  Shaped::resolve(range, size),
 			buffer: vec![S0::zero(); size],
 		}
 	}
 
+	/// Checks if the window was generated by the given function.
+	#[inline(always)]
+	pub fn is<F: 'static>(&self) -> bool {
+		self.function == TypeId::of::<F>()
+	}
+
+	/// The size the window was generated for.
+	#[inline(always)]
+	pub fn size(&self) -> usize {
+		self.size
+	}
+
+	/// The symmetry the window was generated with.
+	#[inline(always)]
+	pub fn symmetry(&self) -> Symmetry {
+		self.symmetry
+	}
+
+	/// The range the window was generated on.
+	#[inline(always)]
+	pub fn range(&self) -> &Shaped<ops::Range<u32>> {
+		&self.range
+	}
+
 	/// Applies the cached window on the given input and return a vector with the
 	/// applied result.
 	#[inline(always)]
@@ -35,18 +67,16 @@ impl<S0: SampleMut> Window<S0> {
 	{
 		let     input  = input.as_stride();
 		let mut output = vec![SO::zero(); input.len()];
-		let     length = input.len();
-	
-		// Check the range are valid for the window.
-		debug_assert!(self.range.is_valid(length));
 
 		self.apply_in(input, &mut *output);
-	
+
 		output
 	}
 
 	/// Applies the cached window on the given input putting the result in the
 	/// given output.
+	///
+	/// Panics if the input is not as long as the window.
 	pub fn apply_in<SO, SI, I, O>(&self, input: I, mut output: O)
 		where SO: SampleMut,
 		      SI: Sample,
@@ -56,41 +86,40 @@ impl<S0: SampleMut> Window<S0> {
 		let     input  = input.as_stride();
 		let mut output = output.as_stride_mut();
 		let     length = input.len();
-	
+
 		// `input` and `output` buffers need to be the same length.
-		debug_assert_eq!(input.len(), output.len());
-	
-		// Check the range are valid for the window.
-		debug_assert!(self.range.is_valid(length));
-	
-		for (index, (input, output)) in input.iter().zip(output.iter_mut()).enumerate() {
-			if index >= self.range.start().unwrap_or(0) as usize &&
-			   index <= self.range.end().unwrap_or(length as u32) as usize
-			{
-				output.set_normalized(input.normalize() * self.buffer[index].normalize());
-			}
+		assert_eq!(input.len(), output.len());
+
+		// The window has to be applied on a buffer of the same size.
+		assert_eq!(length, self.size, "window size mismatch");
+
+		for ((input, output), value) in input.iter().zip(output.iter_mut()).zip(self.buffer.iter()) {
+			output.set_normalized(input.normalize() * value.normalize());
 		}
 	}
-	
+
 	/// Applies the cached window in-place on the given data.
+	///
+	/// Panics if the data is not as long as the window.
 	pub fn apply_on<S, IO>(&self, mut data: IO)
 		where S:  SampleMut,
 		      IO: MutStrided<Elem=S>,
 	{
 		let mut data   = data.as_stride_mut();
 		let     length = data.len();
-	
-		// Check the range are valid for the window.
-		debug_assert!(self.range.is_valid(length));
-	
-		for (index, datum) in data.iter_mut().enumerate() {
-			if index >= self.range.start().unwrap_or(0) as usize &&
-			   index <= self.range.end().unwrap_or(length as u32) as usize
-			{
-				let value = datum.normalize();
-	
-				datum.set_normalized(value * self.buffer[index].normalize());
+
+		// The window has to be applied on a buffer of the same size.
+		assert_eq!(length, self.size, "window size mismatch");
+
+		for (index, (datum, window)) in data.iter_mut().zip(self.buffer.iter()).enumerate() {
+			// avoid a lossy round-trip through the normalized value
+			if let Location::Pass = self.range.locate(index, length) {
+				continue;
 			}
+
+			let value = datum.normalize();
+
+			datum.set_normalized(value * window.normalize());
 		}
 	}
 }